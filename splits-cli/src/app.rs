@@ -1,51 +1,300 @@
-use std::{
-    path::{PathBuf},
-};
+use std::{path::PathBuf, time::Duration};
 
-use splits_core::{Run, Timer};
+use splits_core::{
+    comparison::comparisons, parse_time, CommandExecutor, Comparison, Practice, ResetPolicy, Run,
+    Split, Timer, TimerCommand, TimerSnapshot, TimerState, TimingMethod,
+};
 
-use crate::file::{load_run, save_run};
+use crate::file::{journal_path, load_journal, remove_journal, save_journal, save_run};
 
 pub struct App {
     pub timer: Timer,
     pub run: Run,
     pub should_exit: bool,
+    pub executor: CommandExecutor,
+    pub comparisons: Vec<Box<dyn Comparison>>,
+    pub show_time_save: bool,
+    /// Whether segment statistics are shown instead of the splits.
+    pub show_stats: bool,
+    /// Whether the chance of a personal best and the predicted final time are shown.
+    pub show_prediction: bool,
+    pub reset_policy: ResetPolicy,
+    /// Whether the user is being asked what to keep of the attempt being reset.
+    pub asking_reset: bool,
+    /// The segment being practiced, if in practice mode.
+    pub practice: Option<Practice>,
+    /// Whether practice times can set new best segments.
+    pub practice_golds: bool,
+    /// The split whose time is being typed in.
+    pub editing: Option<SplitEdit>,
+    /// Why the last input was rejected.
+    pub message: Option<String>,
     pub splits_file: PathBuf,
+    /// The category of the game in `splits_file` that `run` belongs to, if the file
+    /// holds a game.
+    pub category: Option<String>,
+}
+
+pub struct SplitEdit {
+    pub index: usize,
+    pub input: String,
 }
 
 impl App {
-    pub fn new<P: Into<PathBuf>>(run: Run, splits_file: P) -> Self {
+    pub fn new<P: Into<PathBuf>>(run: Run, splits_file: P, category: Option<String>) -> Self {
+        let mut timer = Timer::new(run.len());
+        timer.set_offset(run.start_offset());
+        let comparisons = comparisons();
+        let executor = CommandExecutor::new(&mut timer, comparisons.len());
         Self {
-            timer: Timer::new(run.len()),
+            timer,
             run,
             should_exit: false,
+            executor,
+            comparisons,
+            show_time_save: false,
+            show_stats: false,
+            show_prediction: false,
+            reset_policy: ResetPolicy::default(),
+            asking_reset: false,
+            practice: None,
+            practice_golds: false,
+            editing: None,
+            message: None,
             splits_file: splits_file.into(),
+            category,
         }
     }
 
-    pub fn from_file<P: Into<PathBuf>>(path: P) -> anyhow::Result<Self> {
-        let path = path.into();
-        let run = load_run(&path)?;
-        Ok(Self::new(run, path))
+    pub fn on_key(&mut self, c: char) -> anyhow::Result<()> {
+        if self.asking_reset {
+            return self.answer_reset(c);
+        }
+        if self.practice.is_some() {
+            return self.on_practice_key(c);
+        }
+        if self.editing.is_some() {
+            self.on_edit_key(c);
+            return Ok(());
+        }
+        let command = match c {
+            'q' => {
+                self.should_exit = true;
+                return Ok(());
+            }
+            'v' => {
+                self.show_time_save = !self.show_time_save;
+                return Ok(());
+            }
+            'i' => {
+                self.show_stats = !self.show_stats;
+                return Ok(());
+            }
+            'f' => {
+                self.show_prediction = !self.show_prediction;
+                return Ok(());
+            }
+            ' ' => TimerCommand::StartOrSplit,
+            's' => TimerCommand::Skip,
+            'p' => TimerCommand::TogglePause,
+            'g' => TimerCommand::ToggleGameTimePause,
+            't' => TimerCommand::ToggleTimingMethod,
+            'c' => TimerCommand::SwitchComparison,
+            'u' => TimerCommand::Undo,
+            'y' => TimerCommand::Redo,
+            'e' => {
+                if let Some(index) = self.timer.splits().len().checked_sub(1) {
+                    self.editing = Some(SplitEdit {
+                        index,
+                        input: String::new(),
+                    });
+                }
+                return Ok(());
+            }
+            'm' => {
+                if self.timer.state() == TimerState::NotStarted {
+                    self.practice = Some(Practice::new(0));
+                }
+                return Ok(());
+            }
+            'r' => match self.reset_policy.decision() {
+                Some(save) => TimerCommand::Reset { save },
+                None => {
+                    self.asking_reset = self.timer.state() != TimerState::NotStarted;
+                    return Ok(());
+                }
+            },
+            _ => return Ok(()),
+        };
+        self.execute(command)
     }
 
-    pub fn on_key(&mut self, c: char) -> anyhow::Result<()> {
+    fn on_practice_key(&mut self, c: char) -> anyhow::Result<()> {
+        let Some(practice) = &mut self.practice else {
+            return Ok(());
+        };
+        let idle = matches!(
+            practice.timer().state(),
+            TimerState::NotStarted | TimerState::Finished
+        );
         match c {
             'q' => self.should_exit = true,
-            ' ' => self.timer.start_split_or_unpause(),
-            's' => self.timer.skip(),
-            'p' => self.timer.toggle_pause(),
-            'u' => self.timer.undo(),
-            'r' => self.save_and_reset()?,
+            'm' => self.practice = None,
+            ' ' => {
+                if let Some(time) = practice.start_or_split() {
+                    let segment = practice.segment();
+                    self.run
+                        .add_practice_time(segment, time, self.practice_golds);
+                    save_run(&self.splits_file, self.category.as_deref(), &self.run)?;
+                }
+            }
+            'p' => practice.timer_mut().toggle_pause(),
+            'g' => practice.timer_mut().toggle_game_time_pause(),
+            't' => return self.execute(TimerCommand::ToggleTimingMethod),
+            '[' if idle => {
+                self.practice = Some(Practice::new(practice.segment().saturating_sub(1)));
+            }
+            ']' if idle => {
+                let last = self.run.len().saturating_sub(1);
+                self.practice = Some(Practice::new((practice.segment() + 1).min(last)));
+            }
             _ => {}
         }
         Ok(())
     }
 
-    fn save_and_reset(&mut self) -> anyhow::Result<()> {
-        self.run.update(self.timer.splits());
-        save_run(&self.splits_file, &self.run)?;
-        self.timer.reset();
-        Ok(())
+    fn on_edit_key(&mut self, c: char) {
+        let Some(edit) = &mut self.editing else {
+            return;
+        };
+        match c {
+            '[' => edit.index = edit.index.saturating_sub(1),
+            ']' => edit.index = (edit.index + 1).min(self.timer.splits().len() - 1),
+            c if c.is_ascii_digit() || ":.+-".contains(c) => edit.input.push(c),
+            _ => {}
+        }
+    }
+
+    pub fn on_backspace(&mut self) {
+        if let Some(edit) = &mut self.editing {
+            edit.input.pop();
+        }
+    }
+
+    pub fn on_escape(&mut self) {
+        self.editing = None;
+        self.asking_reset = false;
+        self.message = None;
+    }
+
+    /// Sets the split being edited to the time typed in, or skips it if nothing was.
+    pub fn on_enter(&mut self) -> anyhow::Result<()> {
+        let Some(edit) = &self.editing else {
+            return Ok(());
+        };
+        let method = self.timing_method();
+        let time = match parse_split_input(&edit.input) {
+            Ok(time) => time.map(|duration| {
+                // A skipped split takes the other timing method from the next split,
+                // which keeps it in order with its neighbours.
+                let splits = self.timer.splits();
+                let mut time = match splits[edit.index] {
+                    Split::Split(time) => time,
+                    Split::Skipped => splits[edit.index + 1..]
+                        .iter()
+                        .find_map(|split| match split {
+                            Split::Split(time) => Some(*time),
+                            Split::Skipped => None,
+                        })
+                        .unwrap_or_else(|| self.timer.current_time()),
+                };
+                time[method] = duration;
+                time
+            }),
+            Err(message) => {
+                self.message = Some(message);
+                return Ok(());
+            }
+        };
+        match self.timer.set_split(edit.index, time) {
+            Ok(()) => {
+                self.on_escape();
+                self.update_journal()
+            }
+            Err(err) => {
+                self.message = Some(err.to_string());
+                Ok(())
+            }
+        }
+    }
+
+    pub fn execute(&mut self, command: TimerCommand) -> anyhow::Result<()> {
+        let events = self
+            .executor
+            .execute(command, &mut self.timer, &mut self.run);
+        if let TimerCommand::Reset { .. } = command {
+            save_run(&self.splits_file, self.category.as_deref(), &self.run)?;
+        }
+        match events.is_empty() {
+            true => Ok(()),
+            false => self.update_journal(),
+        }
+    }
+
+    fn update_journal(&mut self) -> anyhow::Result<()> {
+        match self.timer.snapshot() {
+            Some(snapshot) => save_journal(&self.journal_path(), &snapshot),
+            None => self.discard_attempt(),
+        }
+    }
+
+    pub fn comparison(&self) -> &dyn Comparison {
+        self.comparisons[self.executor.comparison()].as_ref()
+    }
+
+    pub fn timing_method(&self) -> TimingMethod {
+        self.executor.timing_method()
+    }
+
+    fn answer_reset(&mut self, c: char) -> anyhow::Result<()> {
+        self.asking_reset = false;
+        let policy = match c {
+            'y' => ResetPolicy::Always,
+            'g' => ResetPolicy::GoldsOnly,
+            'n' => ResetPolicy::Discard,
+            _ => return Ok(()),
+        };
+        match policy.decision() {
+            Some(save) => self.execute(TimerCommand::Reset { save }),
+            None => Ok(()),
+        }
+    }
+
+    /// An attempt that was interrupted before it was saved or reset.
+    pub fn interrupted_attempt(&self) -> anyhow::Result<Option<TimerSnapshot>> {
+        load_journal(&self.journal_path())
+    }
+
+    pub fn resume_attempt(&mut self, snapshot: TimerSnapshot) {
+        self.timer.restore(snapshot);
+    }
+
+    pub fn discard_attempt(&mut self) -> anyhow::Result<()> {
+        remove_journal(&self.journal_path())
+    }
+
+    fn journal_path(&self) -> PathBuf {
+        journal_path(&self.splits_file, self.category.as_deref())
+    }
+}
+
+fn parse_split_input(input: &str) -> Result<Option<Duration>, String> {
+    if input.trim().is_empty() {
+        return Ok(None);
+    }
+    let time = parse_time(input).map_err(|err| err.to_string())?;
+    match time.to_duration() {
+        Some(duration) => Ok(Some(duration)),
+        None => Err("split times can't be negative".to_string()),
     }
 }
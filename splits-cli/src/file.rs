@@ -1,15 +1,92 @@
-use std::{fs, path::Path};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
 
-use splits_core::Run;
-use splits_serde::RunSchema;
+use anyhow::Context;
+use splits_core::{Game, Run, TimerSnapshot};
+use splits_serde::{GameSchema, RunSchema, TimerSnapshotSchema};
 
-pub fn load_run(file: &Path) -> anyhow::Result<Run> {
-    Ok(Run::from(serde_yaml::from_str::<RunSchema>(
-        &fs::read_to_string(file)?,
-    )?))
+pub enum SplitsFile {
+    Run(Run),
+    Game(Game),
 }
 
-pub fn save_run(file: &Path, run: &Run) -> anyhow::Result<()> {
-    fs::write(file, serde_yaml::to_string(&RunSchema::from(run))?)?;
+/// Loads a splits file, which holds a game if it lists categories and a single run
+/// otherwise.
+pub fn load_splits(file: &Path) -> anyhow::Result<SplitsFile> {
+    let contents = fs::read_to_string(file)?;
+    let value: serde_yaml::Value = serde_yaml::from_str(&contents)?;
+    // Parsed again as the schema it holds, so errors point at the right line.
+    Ok(match value.get("categories") {
+        Some(_) => SplitsFile::Game(serde_yaml::from_str::<GameSchema>(&contents)?.try_into()?),
+        None => SplitsFile::Run(serde_yaml::from_str::<RunSchema>(&contents)?.into()),
+    })
+}
+
+/// Saves `run`, replacing the run of `category` when the file holds a game.
+pub fn save_run(file: &Path, category: Option<&str>, run: &Run) -> anyhow::Result<()> {
+    let contents = match category {
+        Some(name) => {
+            let SplitsFile::Game(mut game) = load_splits(file)? else {
+                anyhow::bail!("{} no longer holds a game", file.display());
+            };
+            *game
+                .category_mut(name)
+                .with_context(|| format!("category `{name}` no longer exists"))?
+                .run_mut() = run.clone();
+            serde_yaml::to_string(&GameSchema::from(&game))?
+        }
+        None => serde_yaml::to_string(&RunSchema::from(run))?,
+    };
+    fs::write(file, contents)?;
     Ok(())
 }
+
+/// The sidecar file the in-progress attempt of `splits_file` is journaled to. Every
+/// category of a game gets its own journal.
+pub fn journal_path(splits_file: &Path, category: Option<&str>) -> PathBuf {
+    match category {
+        Some(name) => {
+            // Anything else is escaped as its code point, so no two names share a
+            // journal.
+            let name: String = name
+                .chars()
+                .map(|c| match c.is_alphanumeric() {
+                    true => c.to_string(),
+                    false => format!("_{:x}_", c as u32),
+                })
+                .collect();
+            splits_file.with_extension(format!("{name}.journal.yaml"))
+        }
+        None => splits_file.with_extension("journal.yaml"),
+    }
+}
+
+pub fn load_journal(file: &Path) -> anyhow::Result<Option<TimerSnapshot>> {
+    match fs::read_to_string(file) {
+        Ok(contents) => Ok(Some(TimerSnapshot::from(serde_yaml::from_str::<
+            TimerSnapshotSchema,
+        >(&contents)?))),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+pub fn save_journal(file: &Path, snapshot: &TimerSnapshot) -> anyhow::Result<()> {
+    // Write to a temporary file first so a crash mid-write can't corrupt the journal.
+    let tmp = file.with_extension("tmp");
+    fs::write(
+        &tmp,
+        serde_yaml::to_string(&TimerSnapshotSchema::from(snapshot))?,
+    )?;
+    fs::rename(tmp, file)?;
+    Ok(())
+}
+
+pub fn remove_journal(file: &Path) -> anyhow::Result<()> {
+    match fs::remove_file(file) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.into()),
+        _ => Ok(()),
+    }
+}
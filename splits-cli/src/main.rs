@@ -1,15 +1,17 @@
 mod app;
 mod file;
+mod stats;
 mod style;
+mod survival;
 mod ui;
 
 use std::{
-    io,
-    path::PathBuf,
+    io::{self, Write},
+    path::{Path, PathBuf},
     time::{Duration, Instant},
 };
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use crossterm::{
     event::{self, Event, KeyCode, KeyEventKind},
     execute,
@@ -20,18 +22,279 @@ use ratatui::{
     Terminal,
 };
 
-use crate::app::App;
+use anyhow::Context;
+use splits_core::{
+    BestChange, Game, OutlierKind, ResetPolicy, Run, Time, TimeFormatter, TimingMethod,
+    OUTLIER_FENCE,
+};
+
+use crate::{
+    app::App,
+    file::{load_splits, save_run, SplitsFile},
+};
 
 #[derive(Parser)]
+#[clap(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Args {
-    splits_file: PathBuf,
+    #[clap(subcommand)]
+    command: Option<Command>,
+    #[clap(required = true)]
+    splits_file: Option<PathBuf>,
+    /// The category to run when the splits file holds a game
+    #[clap(long, short)]
+    category: Option<String>,
     #[clap(long, short, default_value_t = 10)]
     tick_rate: i32,
+    /// What to keep of an attempt when resetting: always, golds-only, ask or discard
+    #[clap(long, short, default_value_t = ResetPolicy::Always)]
+    reset_policy: ResetPolicy,
+    /// Let practice times set new best segments
+    #[clap(long)]
+    practice_golds: bool,
+    /// Rebuild the bests from the recorded attempts when loading the splits file
+    #[clap(long, global = true)]
+    recompute_bests: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print statistics about every segment's history
+    Stats {
+        splits_file: PathBuf,
+        /// The category to use when the splits file holds a game
+        #[clap(long, short)]
+        category: Option<String>,
+        /// Use game time instead of real time
+        #[clap(long, short)]
+        game_time: bool,
+    },
+    /// List segment times far off the rest of their history, and exclude or delete
+    /// them
+    Clean {
+        splits_file: PathBuf,
+        /// The category to use when the splits file holds a game
+        #[clap(long, short)]
+        category: Option<String>,
+        /// Use game time instead of real time
+        #[clap(long, short)]
+        game_time: bool,
+        /// How many interquartile ranges off a segment time has to be
+        #[clap(long, short, default_value_t = OUTLIER_FENCE)]
+        fence: f64,
+        /// Leave the outliers out of the history, keeping their attempts
+        #[clap(long, conflicts_with = "delete")]
+        exclude: bool,
+        /// Delete the attempts with outliers
+        #[clap(long)]
+        delete: bool,
+    },
+    /// Print how many attempts reached and were reset in every segment
+    Survival {
+        splits_file: PathBuf,
+        /// The category to use when the splits file holds a game
+        #[clap(long, short)]
+        category: Option<String>,
+    },
 }
 
 fn main() -> anyhow::Result<()> {
     let args = Args::parse();
 
+    match args.command {
+        Some(Command::Stats {
+            splits_file,
+            category,
+            game_time,
+        }) => {
+            let (run, _) = open_run(&splits_file, category, args.recompute_bests)?;
+            let method = match game_time {
+                true => TimingMethod::GameTime,
+                false => TimingMethod::RealTime,
+            };
+            stats::print(&run, method);
+            Ok(())
+        }
+        Some(Command::Clean {
+            splits_file,
+            category,
+            game_time,
+            fence,
+            exclude,
+            delete,
+        }) => {
+            let (mut run, category) = open_run(&splits_file, category, args.recompute_bests)?;
+            let method = match game_time {
+                true => TimingMethod::GameTime,
+                false => TimingMethod::RealTime,
+            };
+            let outliers = run.outliers(method, fence);
+            for outlier in &outliers {
+                println!(
+                    "Attempt {}: {} took {} ({})",
+                    outlier.attempt,
+                    run.segments()[outlier.segment].title(),
+                    format_time(outlier.time),
+                    match outlier.kind {
+                        OutlierKind::Short => "too short",
+                        OutlierKind::Long => "too long",
+                    }
+                );
+            }
+            if outliers.is_empty() {
+                println!("No outliers found");
+            } else if exclude {
+                run.exclude_outliers(&outliers);
+                save_run(&splits_file, category.as_deref(), &run)?;
+                println!("Excluded {} segment times", outliers.len());
+            } else if delete {
+                let ids: Vec<_> = outliers.iter().map(|outlier| outlier.attempt).collect();
+                let removed = run.remove_attempts(&ids);
+                save_run(&splits_file, category.as_deref(), &run)?;
+                println!("Deleted {} attempts", removed.len());
+            }
+            Ok(())
+        }
+        Some(Command::Survival {
+            splits_file,
+            category,
+        }) => {
+            let (run, _) = open_run(&splits_file, category, args.recompute_bests)?;
+            survival::print(&run);
+            Ok(())
+        }
+        None => run_timer(args),
+    }
+}
+
+/// Loads the run in `splits_file`, picking a category if the file holds a game.
+/// Returns the run and the name of its category. With `recompute_bests`, the bests
+/// are rebuilt from the recorded attempts and every one that changed is printed.
+fn open_run(
+    splits_file: &Path,
+    category: Option<String>,
+    recompute_bests: bool,
+) -> anyhow::Result<(Run, Option<String>)> {
+    let (mut run, category) = match load_splits(splits_file)? {
+        SplitsFile::Run(run) => {
+            if category.is_some() {
+                anyhow::bail!("{} holds a single run", splits_file.display());
+            }
+            (run, None)
+        }
+        SplitsFile::Game(game) => {
+            let name = match category {
+                Some(name) => name,
+                None => pick_category(&game)?,
+            };
+            let run = game
+                .category(&name)
+                .with_context(|| format!("{} has no category `{name}`", game.title()))?
+                .run()
+                .clone();
+            (run, Some(name))
+        }
+    };
+    if recompute_bests {
+        print_recomputed_bests(&mut run);
+    }
+    Ok((run, category))
+}
+
+/// Rebuilds the bests of `run` from its recorded attempts and prints every one
+/// that changed. Returns whether any did.
+fn print_recomputed_bests(run: &mut Run) -> bool {
+    let changes = run.recompute_bests();
+    if !changes.is_empty() {
+        println!("Recomputed bests that didn't match the history:");
+    }
+    for change in &changes {
+        println!("  {}", describe_change(run, change));
+    }
+    !changes.is_empty()
+}
+
+fn describe_change(run: &Run, change: &BestChange) -> String {
+    let format = |time: Option<Time>| match time {
+        Some(time) if time.real_time == time.game_time => format_time(time.real_time),
+        Some(time) => format!(
+            "{} ({} game time)",
+            format_time(time.real_time),
+            format_time(time.game_time)
+        ),
+        None => "-".to_string(),
+    };
+    match change {
+        BestChange::BestTime {
+            segment,
+            stored,
+            recomputed,
+        } => format!(
+            "{} best split: {} -> {}",
+            run.segments()[*segment].title(),
+            format(*stored),
+            format(*recomputed)
+        ),
+        BestChange::BestSegment {
+            segment,
+            stored,
+            recomputed,
+        } => format!(
+            "{} best segment: {} -> {}",
+            run.segments()[*segment].title(),
+            format(*stored),
+            format(*recomputed)
+        ),
+        BestChange::PersonalBest {
+            method,
+            stored,
+            recomputed,
+        } => {
+            let final_time = |splits: &[Option<Duration>]| {
+                splits
+                    .last()
+                    .copied()
+                    .flatten()
+                    .map_or_else(|| "-".to_string(), format_time)
+            };
+            format!(
+                "Personal best ({method}): {} -> {}",
+                final_time(stored),
+                final_time(recomputed)
+            )
+        }
+    }
+}
+
+fn format_time(time: Duration) -> String {
+    TimeFormatter::default().format(time)
+}
+
+fn run_timer(args: Args) -> anyhow::Result<()> {
+    let splits_file = args.splits_file.expect("required without a subcommand");
+    let (mut run, category) = open_run(&splits_file, args.category, false)?;
+    if args.recompute_bests {
+        // The timer takes over the screen, so the changes are confirmed before
+        // they can be saved with the next attempt.
+        let mut recomputed = run.clone();
+        if !print_recomputed_bests(&mut recomputed) || confirm("Keep the recomputed bests?")? {
+            run = recomputed;
+        }
+    }
+    let mut app = App::new(run, splits_file, category);
+    app.reset_policy = args.reset_policy;
+    app.practice_golds = args.practice_golds;
+    if let Some(snapshot) = app.interrupted_attempt()? {
+        let question = format!(
+            "Found an interrupted attempt with {} splits. Resume it?",
+            snapshot.splits.len()
+        );
+        if confirm(&question)? {
+            app.resume_attempt(snapshot);
+        } else {
+            app.discard_attempt()?;
+        }
+    }
+
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
@@ -39,11 +302,6 @@ fn main() -> anyhow::Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // let app = match args.splits_file.try_exists()? {
-    //     true => App::from_file(args.splits_file),
-    //     false => App::new(Run::, splits_file)
-    // }
-    let app = App::from_file(args.splits_file)?;
     let res = run_app(
         &mut terminal,
         app,
@@ -56,6 +314,49 @@ fn main() -> anyhow::Result<()> {
     res
 }
 
+fn confirm(question: &str) -> io::Result<bool> {
+    print!("{question} [y/N] ");
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+/// Asks which category of `game` to run, unless there is only one.
+fn pick_category(game: &Game) -> anyhow::Result<String> {
+    let names: Vec<_> = game.categories().iter().map(|c| c.name()).collect();
+    match names[..] {
+        [] => anyhow::bail!("{} has no categories", game.title()),
+        [name] => return Ok(name.to_string()),
+        _ => {}
+    }
+    println!("{}", game.title());
+    for (number, category) in game.categories().iter().enumerate() {
+        let variables: Vec<_> = category.variables().values().map(String::as_str).collect();
+        match variables.is_empty() {
+            true => println!("  {}. {}", number + 1, category.name()),
+            false => println!(
+                "  {}. {} ({})",
+                number + 1,
+                category.name(),
+                variables.join(", ")
+            ),
+        }
+    }
+    print!("Category: ");
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    let answer = answer.trim();
+    match answer.parse::<usize>() {
+        Ok(number) => names
+            .get(number.wrapping_sub(1))
+            .map(|name| name.to_string()),
+        Err(_) => names.contains(&answer).then(|| answer.to_string()),
+    }
+    .with_context(|| format!("no category `{answer}`"))
+}
+
 fn run_app<B: Backend>(
     terminal: &mut Terminal<B>,
     mut app: App,
@@ -72,9 +373,11 @@ fn run_app<B: Backend>(
         if event::poll(timeout)? {
             if let Event::Key(key) = event::read()? {
                 if key.kind == KeyEventKind::Press {
-                    #[allow(clippy::single_match)]
                     match key.code {
                         KeyCode::Char(c) => app.on_key(c)?,
+                        KeyCode::Enter => app.on_enter()?,
+                        KeyCode::Backspace => app.on_backspace(),
+                        KeyCode::Esc => app.on_escape(),
                         _ => {}
                     }
                 }
@@ -0,0 +1,73 @@
+use splits_core::{Run, TimeFormatter, TimingMethod};
+
+/// The column titles of [`rows`].
+pub const HEADER: [&str; 9] = [
+    "Segment",
+    "Count",
+    "Best",
+    "Median",
+    "Mean",
+    "Std Dev",
+    "90th",
+    "Worst",
+    "Consistency",
+];
+
+/// One row of formatted statistics per segment of `run`, in columns matching
+/// [`HEADER`].
+pub fn rows(run: &Run, method: TimingMethod) -> Vec<[String; 9]> {
+    let format = |time| TimeFormatter::default().format(time);
+    run.segments()
+        .iter()
+        .enumerate()
+        .map(|(index, segment)| {
+            let title = segment.title().to_string();
+            match run.segment_stats(index, method) {
+                Some(stats) => [
+                    title,
+                    stats.count().to_string(),
+                    format(stats.best()),
+                    format(stats.median()),
+                    format(stats.mean()),
+                    format(stats.std_dev()),
+                    format(stats.percentile(0.9)),
+                    format(stats.worst()),
+                    format!("{:.0}%", stats.consistency() * 100.0),
+                ],
+                None => {
+                    let mut row = [(); 9].map(|_| "-".to_string());
+                    row[0] = title;
+                    row[1] = "0".to_string();
+                    row
+                }
+            }
+        })
+        .collect()
+}
+
+/// Prints the statistics of every segment of `run` as a table.
+pub fn print(run: &Run, method: TimingMethod) {
+    let rows = rows(run, method);
+    let widths: Vec<_> = (0..HEADER.len())
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].chars().count())
+                .chain([HEADER[column].len()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    println!("{} ({method})", run.title());
+    for row in [HEADER.map(str::to_string)].iter().chain(&rows) {
+        let line: Vec<_> = row
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(column, (cell, &width))| match column {
+                0 => format!("{cell:<width$}"),
+                _ => format!("{cell:>width$}"),
+            })
+            .collect();
+        println!("{}", line.join("  "));
+    }
+}
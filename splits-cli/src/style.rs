@@ -1,4 +1,4 @@
-use ratatui::style::{Color, Style};
+use ratatui::style::{Color, Modifier, Style};
 
 pub fn red() -> Style {
     Style::default().fg(Color::Red)
@@ -8,10 +8,18 @@ pub fn green() -> Style {
     Style::default().fg(Color::Green)
 }
 
+pub fn light_red() -> Style {
+    Style::default().fg(Color::LightRed)
+}
+
+pub fn light_green() -> Style {
+    Style::default().fg(Color::LightGreen)
+}
+
 pub fn yellow() -> Style {
     Style::default().fg(Color::Yellow)
 }
 
-pub fn white() -> Style {
-    Style::default().fg(Color::White)
+pub fn bold() -> Style {
+    Style::default().add_modifier(Modifier::BOLD)
 }
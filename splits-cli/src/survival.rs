@@ -0,0 +1,54 @@
+use splits_core::Run;
+
+/// The length of the longest bar in the histogram.
+const BAR_WIDTH: usize = 40;
+
+/// Prints where the attempts of `run` were reset, as a table followed by a
+/// histogram of resets per segment.
+pub fn print(run: &Run) {
+    let survival = run.survival();
+    let titles: Vec<_> = run
+        .segments()
+        .iter()
+        .map(|segment| segment.title())
+        .collect();
+    let title_width = titles
+        .iter()
+        .map(|title| title.chars().count())
+        .chain(["Segment".len()])
+        .max()
+        .unwrap_or_default();
+
+    println!("{} ({} attempts)", run.title(), run.attempts().len());
+    println!(
+        "{:<title_width$}  {:>7}  {:>6}  {:>7}  {:>8}",
+        "Segment", "Reached", "Resets", "Reset %", "Survival"
+    );
+    for (title, segment) in titles.iter().zip(&survival) {
+        println!(
+            "{title:<title_width$}  {:>7}  {:>6}  {:>6.1}%  {:>7.1}%",
+            segment.reached,
+            segment.reset,
+            segment.reset_rate() * 100.0,
+            segment.survival * 100.0
+        );
+    }
+
+    println!();
+    let most = survival
+        .iter()
+        .map(|segment| segment.reset)
+        .max()
+        .unwrap_or(0);
+    for (title, segment) in titles.iter().zip(&survival) {
+        let bar = match most {
+            0 => 0,
+            most => (segment.reset * BAR_WIDTH).div_ceil(most),
+        };
+        println!(
+            "{title:<title_width$}  {} {}",
+            "#".repeat(bar),
+            segment.reset
+        );
+    }
+}
@@ -1,91 +1,264 @@
-use std::time::Duration;
+use std::{ops::Range, time::Duration};
 
 use ratatui::{
     backend::Backend,
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Style,
     text::Span,
     widgets::{Block, Borders, Paragraph, Row, Table},
     Frame,
 };
-use splits_core::Split;
+use splits_core::{
+    Pace, Practice, SignedDuration, Split, TimeFormatter, TimeStyle, PREDICTION_SAMPLES,
+};
 
-use crate::{app::App, style};
+use crate::{app::App, stats, style};
 
 pub fn draw<B: Backend>(frame: &mut Frame<B>, app: &App) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Length(9), Constraint::Length(1)])
+        .constraints([
+            Constraint::Length(9),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
         .split(frame.size());
+    if let Some(practice) = &app.practice {
+        return draw_practice(frame, &chunks, app, practice);
+    }
+    if app.show_stats {
+        return draw_stats(frame, &chunks, app);
+    }
 
-    let items: Vec<Row> = app
-        .run
-        .segments()
-        .iter()
-        .zip(
-            app.timer
-                .splits()
-                .iter()
-                .map(Some)
-                .chain(std::iter::repeat(None)),
-        )
-        .enumerate()
-        .map(|(index, (segment, split))| {
-            let title = Span::raw(segment.title());
-            let mut row = vec![title];
-            let time = match split {
-                Some(split) => match split {
-                    Split::Skipped => None,
-                    Split::Split(time) => Some(*time),
-                },
-                None => {
-                    if app.timer.current_index() == Some(index) {
-                        Some(app.timer.current_time())
-                    } else {
-                        segment.best_time()
-                    }
-                }
-            };
-            if let Some(time) = time {
-                let style = match segment.best_time() {
-                    Some(best) => match time.cmp(&best) {
-                        std::cmp::Ordering::Less => style::green(),
-                        std::cmp::Ordering::Equal => style::yellow(),
-                        std::cmp::Ordering::Greater => style::red(),
-                    },
-                    None => style::white(),
-                };
-
-                if let Some(best) = segment.best_time() {
-                    if best != time {
-                        let diff = time.as_secs_f64() - best.as_secs_f64();
-                        row.push(Span::styled(format!("{:+.2}", diff), style));
-                    } else {
-                        row.push(Span::raw(""));
-                    }
-                } else {
-                    row.push(Span::raw(""));
+    let comparison = app.comparison().splits(&app.run, app.timing_method());
+    let deltas = app.run.deltas(&app.timer, &comparison, app.timing_method());
+    let time_save = |range: Range<usize>| {
+        range
+            .map(|index| {
+                app.run
+                    .possible_time_save(index, &comparison, app.timing_method())
+            })
+            .sum::<Option<Duration>>()
+    };
+    let row = |index: usize, title: String, time_save: Option<Duration>| {
+        let mut row = vec![Span::raw(title)];
+        let delta = deltas.get(index);
+        let time = match (app.timer.splits().get(index), delta) {
+            (Some(Split::Split(time)), _) => Some(time[app.timing_method()]),
+            (Some(Split::Skipped), _) => None,
+            (None, Some(delta)) if delta.live => {
+                Some(app.timer.current_time()[app.timing_method()])
+            }
+            (None, _) => comparison[index],
+        };
+        match delta.and_then(|delta| delta.delta.zip(delta.pace)) {
+            Some((delta, pace)) => row.push(Span::styled(
+                TimeFormatter::new(TimeStyle::Delta).format(delta),
+                pace_style(pace),
+            )),
+            None => row.push(Span::raw("")),
+        }
+        row.push(Span::raw(time.map(format_time).unwrap_or_default()));
+        if app.show_time_save {
+            row.push(Span::raw(format_optional_time(time_save)));
+        }
+        Row::new(row)
+    };
+
+    // Only the group of the current segment is expanded, every other group is
+    // shown as a single row ending at its last split.
+    let current = app.timer.current_index();
+    let mut items = Vec::new();
+    let mut index = 0;
+    while index < app.run.len() {
+        let group = app
+            .run
+            .groups()
+            .iter()
+            .find(|group| group.range().start == index);
+        match group {
+            Some(group) if current.is_some_and(|current| group.contains(current)) => {
+                items.push(Row::new(vec![Span::raw(group.title().to_string())]));
+                for index in group.range() {
+                    let title = format!("  {}", app.run.segments()[index].title());
+                    items.push(row(index, title, time_save(index..index + 1)));
                 }
-                row.push(Span::raw(format_time(time)));
+                index = group.range().end;
+            }
+            Some(group) => {
+                let title = group.title().to_string();
+                items.push(row(group.last(), title, time_save(group.range())));
+                index = group.range().end;
+            }
+            None => {
+                let title = app.run.segments()[index].title().to_string();
+                items.push(row(index, title, time_save(index..index + 1)));
+                index += 1;
             }
-            Row::new(row)
-        })
-        .collect();
+        }
+    }
 
+    let mut widths = vec![
+        Constraint::Length(16),
+        Constraint::Length(8),
+        Constraint::Length(10),
+    ];
+    if app.show_time_save {
+        widths.push(Constraint::Length(8));
+    }
     let table = Table::new(items)
-        .block(Block::default().borders(Borders::ALL))
-        .widths(&[
-            Constraint::Length(16),
-            Constraint::Length(5),
-            Constraint::Length(5),
-        ]);
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "{} vs {}",
+            app.timing_method(),
+            app.comparison().name()
+        )))
+        .widths(&widths);
 
     frame.render_widget(table, chunks[0]);
-    frame.render_widget(
-        Paragraph::new(Span::raw(format_time(app.timer.current_time()))),
-        chunks[1],
+    let current_time = match app.timer.countdown() {
+        Some(countdown) => format_countdown(countdown),
+        None => format_time(app.timer.current_time()[app.timing_method()]),
+    };
+    frame.render_widget(Paragraph::new(Span::raw(current_time)), chunks[1]);
+
+    let sum_of_best = app.run.sum_of_best(app.timing_method());
+    let best_possible_time = app.run.best_possible_time(&app.timer, app.timing_method());
+    let mut info = format!(
+        "Sum of Best: {}  Best Possible: {}",
+        format_optional_time(sum_of_best),
+        format_optional_time(best_possible_time),
     );
+    if app.show_time_save {
+        let time_save = app.run.total_possible_time_save(
+            app.timer.current_index().unwrap_or(0),
+            &comparison,
+            app.timing_method(),
+        );
+        info.push_str(&format!("  Possible Time Save: {}", format_time(time_save)));
+    }
+    if app.show_prediction {
+        let prediction = app
+            .run
+            .predict(&app.timer, app.timing_method(), PREDICTION_SAMPLES);
+        match prediction {
+            Some(prediction) => {
+                if let Some(probability) = prediction.pb_probability {
+                    info.push_str(&format!("  PB Chance: {:.0}%", probability * 100.0));
+                }
+                info.push_str(&format!(
+                    "  Predicted: {} ({} - {})",
+                    format_time(prediction.median),
+                    format_time(prediction.low),
+                    format_time(prediction.high)
+                ));
+            }
+            None => info.push_str("  Predicted: -"),
+        }
+    }
+    if app.asking_reset {
+        info = "Save attempt? [y]es  [g]olds only  [n]o".to_string();
+    }
+    if let Some(edit) = &app.editing {
+        info = format!(
+            "Split {} ({}): {}_  [enter] save  [esc] cancel",
+            edit.index + 1,
+            app.run.segments()[edit.index].title(),
+            edit.input
+        );
+    }
+    if let Some(message) = &app.message {
+        info.push_str(&format!("  {message}"));
+    }
+    frame.render_widget(Paragraph::new(Span::raw(info)), chunks[2]);
+}
+
+/// How many of the latest practice repetitions the rolling average covers.
+const PRACTICE_AVERAGE: usize = 5;
+
+fn draw_practice<B: Backend>(
+    frame: &mut Frame<B>,
+    chunks: &[Rect],
+    app: &App,
+    practice: &Practice,
+) {
+    let method = app.timing_method();
+    let segment = &app.run.segments()[practice.segment()];
+    let rows = vec![
+        (
+            "Best Segment",
+            format_optional_time(segment.best_segment().map(|time| time[method])),
+        ),
+        ("Session Best", format_optional_time(practice.best(method))),
+        (
+            "Average",
+            format_optional_time(practice.rolling_average(method, PRACTICE_AVERAGE)),
+        ),
+        ("Repetitions", practice.session().len().to_string()),
+        ("All Time", segment.practice_history().len().to_string()),
+    ];
+    let table = Table::new(
+        rows.into_iter()
+            .map(|(label, value)| Row::new(vec![Span::raw(label), Span::raw(value)])),
+    )
+    .block(Block::default().borders(Borders::ALL).title(format!(
+        "Practice {} ({})",
+        segment.title(),
+        method
+    )))
+    .widths(&[Constraint::Length(16), Constraint::Length(10)]);
+    frame.render_widget(table, chunks[0]);
+
+    let current_time = format_time(practice.timer().current_time()[method]);
+    frame.render_widget(Paragraph::new(Span::raw(current_time)), chunks[1]);
+    let help = "[space] start/split  [ and ] change segment  [m] leave practice";
+    frame.render_widget(Paragraph::new(Span::raw(help)), chunks[2]);
+}
+
+fn draw_stats<B: Backend>(frame: &mut Frame<B>, chunks: &[Rect], app: &App) {
+    let method = app.timing_method();
+    let rows = stats::rows(&app.run, method)
+        .into_iter()
+        .map(|row| Row::new(row.map(Span::raw).to_vec()));
+    let mut widths = vec![Constraint::Length(16), Constraint::Length(5)];
+    widths.extend([Constraint::Length(10); 6]);
+    widths.push(Constraint::Length(11));
+    let table = Table::new(rows)
+        .header(Row::new(stats::HEADER.map(Span::raw).to_vec()).style(style::bold()))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Statistics ({method})")),
+        )
+        .widths(&widths);
+    frame.render_widget(table, chunks[0]);
+
+    let current_time = format_time(app.timer.current_time()[method]);
+    frame.render_widget(Paragraph::new(Span::raw(current_time)), chunks[1]);
+    let help = "[t] timing method  [i] back to splits";
+    frame.render_widget(Paragraph::new(Span::raw(help)), chunks[2]);
+}
+
+fn pace_style(pace: Pace) -> Style {
+    match pace {
+        Pace::AheadGaining => style::green(),
+        Pace::AheadLosing => style::light_green(),
+        Pace::BehindGaining => style::light_red(),
+        Pace::BehindLosing => style::red(),
+        Pace::Gold => style::yellow(),
+    }
 }
 
 fn format_time(duration: Duration) -> String {
-    format!("{:.2}", duration.as_secs_f64())
+    TimeFormatter::default().format(duration)
+}
+
+/// Formats the time left before the start, always with minutes, as in `-0:01.35`.
+fn format_countdown(countdown: Duration) -> String {
+    TimeFormatter::default()
+        .with_minutes(true)
+        .format(SignedDuration::negative(countdown))
+}
+
+fn format_optional_time(duration: Option<Duration>) -> String {
+    duration.map_or_else(|| "-".to_string(), format_time)
 }
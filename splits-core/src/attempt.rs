@@ -0,0 +1,162 @@
+use std::time::{Duration, SystemTime};
+
+use crate::time::Time;
+
+/// A single run through the splits, whether it was finished or reset early.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Attempt {
+    id: u32,
+    started: Option<SystemTime>,
+    ended: Option<SystemTime>,
+    paused_time: Duration,
+    splits: Vec<Option<Time>>,
+    reset_segment: Option<usize>,
+    excluded: Vec<usize>,
+}
+
+impl Attempt {
+    pub fn new(
+        id: u32,
+        started: Option<SystemTime>,
+        ended: Option<SystemTime>,
+        paused_time: Duration,
+        splits: Vec<Option<Time>>,
+    ) -> Self {
+        // Without a record of where it was reset, an attempt is assumed to have
+        // been reset in the segment after its last split.
+        let reset_segment = splits.last().is_some_and(Option::is_none).then(|| {
+            splits
+                .iter()
+                .rposition(Option::is_some)
+                .map_or(0, |i| i + 1)
+        });
+        Self {
+            id,
+            started,
+            ended,
+            paused_time,
+            splits,
+            reset_segment,
+            excluded: Vec::new(),
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn started(&self) -> Option<SystemTime> {
+        self.started
+    }
+
+    pub fn ended(&self) -> Option<SystemTime> {
+        self.ended
+    }
+
+    pub fn paused_time(&self) -> Duration {
+        self.paused_time
+    }
+
+    /// The split time of every segment, `None` where the segment was skipped or
+    /// never reached.
+    pub fn splits(&self) -> &[Option<Time>] {
+        &self.splits
+    }
+
+    pub fn split(&self, index: usize) -> Option<Time> {
+        self.splits.get(index).copied().flatten()
+    }
+
+    /// The split time at `index`, unless the duration of a segment up to it was
+    /// excluded. An early split like a double tap throws off every split after it.
+    pub(crate) fn trusted_split(&self, index: usize) -> Option<Time> {
+        match self.excluded.first() {
+            Some(&excluded) if excluded <= index => None,
+            _ => self.split(index),
+        }
+    }
+
+    /// The duration of the segment at `index`, if both the segment and the one
+    /// before it were split and the duration wasn't excluded.
+    pub fn segment_time(&self, index: usize) -> Option<Time> {
+        if self.is_excluded(index) {
+            return None;
+        }
+        let previous = match index {
+            0 => Time::ZERO,
+            _ => self.split(index - 1)?,
+        };
+        self.split(index)?.checked_sub(previous)
+    }
+
+    /// The index of the segment the attempt was reset in, `None` if it was
+    /// finished.
+    pub fn reset_segment(&self) -> Option<usize> {
+        self.reset_segment
+    }
+
+    /// Records where the attempt was reset, which its splits can't tell apart
+    /// from skipped segments.
+    pub fn set_reset_segment(&mut self, reset_segment: Option<usize>) {
+        self.reset_segment = reset_segment;
+    }
+
+    /// The indices of the segments whose durations are ignored, such as outliers.
+    /// Their split times are kept.
+    pub fn excluded(&self) -> &[usize] {
+        &self.excluded
+    }
+
+    pub fn is_excluded(&self, index: usize) -> bool {
+        self.excluded.contains(&index)
+    }
+
+    pub fn exclude(&mut self, index: usize) {
+        if !self.is_excluded(index) {
+            self.excluded.push(index);
+            self.excluded.sort();
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.final_time().is_some()
+    }
+
+    pub fn final_time(&self) -> Option<Time> {
+        self.splits.last().copied().flatten()
+    }
+
+    pub(crate) fn resize(&mut self, len: usize) {
+        let old_len = self.splits.len();
+        let was_finished = self.is_finished();
+        self.splits.resize(len, None);
+        if self.is_finished() {
+            self.reset_segment = None;
+        } else if was_finished {
+            self.reset_segment = Some(old_len);
+        }
+        self.remap_segments(|index| index);
+    }
+
+    pub(crate) fn splits_mut(&mut self) -> &mut Vec<Option<Time>> {
+        &mut self.splits
+    }
+
+    /// Moves the segment indices the attempt keeps apart from its splits to where
+    /// `map` puts them, after the splits have been edited. A reset past the last
+    /// segment moves into it, or is dropped if the attempt now ends in a split.
+    pub(crate) fn remap_segments(&mut self, map: impl Fn(usize) -> usize) {
+        let len = self.splits.len();
+        self.reset_segment = match self.reset_segment.map(&map) {
+            _ if self.is_finished() => None,
+            Some(index) if index >= len => len.checked_sub(1),
+            reset_segment => reset_segment,
+        };
+        for index in &mut self.excluded {
+            *index = map(*index);
+        }
+        self.excluded.retain(|&index| index < len);
+        self.excluded.sort();
+        self.excluded.dedup();
+    }
+}
@@ -0,0 +1,188 @@
+use std::time::Duration;
+
+use crate::{
+    run::Run,
+    time::{Time, TimingMethod},
+};
+
+/// A best that was rebuilt to a different value than it had.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BestChange {
+    BestTime {
+        segment: usize,
+        stored: Option<Time>,
+        recomputed: Option<Time>,
+    },
+    BestSegment {
+        segment: usize,
+        stored: Option<Time>,
+        recomputed: Option<Time>,
+    },
+    PersonalBest {
+        method: TimingMethod,
+        stored: Vec<Option<Duration>>,
+        recomputed: Vec<Option<Duration>>,
+    },
+}
+
+impl Run {
+    /// Rebuilds the best split times, best segments and personal best from the
+    /// recorded attempts alone, with best split times coming from finished ones, and
+    /// returns every best that changed. Bests set by practice or by attempts that
+    /// weren't recorded are lost. The personal best is kept if no recorded attempt
+    /// was finished.
+    pub fn recompute_bests(&mut self) -> Vec<BestChange> {
+        let mut changes = Vec::new();
+        for index in 0..self.len() {
+            let best_time = self.recompute_best_time(index);
+            let mut best_segment = self.recompute_best_segment(index);
+            let segment = &mut self.segments[index];
+            // Legacy split times can't tell the duration of a segment, so the best
+            // segment they were written with is kept if nothing recorded beats it.
+            if !segment.legacy_history.is_empty() {
+                best_segment = best_segment
+                    .into_iter()
+                    .chain(segment.best_segment)
+                    .reduce(Time::min);
+            }
+            if segment.best_time != best_time {
+                changes.push(BestChange::BestTime {
+                    segment: index,
+                    stored: segment.best_time,
+                    recomputed: best_time,
+                });
+                segment.best_time = best_time;
+            }
+            if segment.best_segment != best_segment {
+                changes.push(BestChange::BestSegment {
+                    segment: index,
+                    stored: segment.best_segment,
+                    recomputed: best_segment,
+                });
+                segment.best_segment = best_segment;
+            }
+        }
+
+        let stored = TimingMethod::ALL.map(|method| self.personal_best(method).to_vec());
+        self.recompute_personal_best();
+        for (method, stored) in TimingMethod::ALL.into_iter().zip(stored) {
+            if stored != self.personal_best(method) {
+                changes.push(BestChange::PersonalBest {
+                    method,
+                    stored,
+                    recomputed: self.personal_best(method).to_vec(),
+                });
+            }
+        }
+        changes
+    }
+
+    /// The best split time at `index` of the finished attempts, like saving an
+    /// attempt keeps it, leaving out splits after an excluded segment. Legacy split
+    /// times count as well.
+    pub(crate) fn recompute_best_time(&self, index: usize) -> Option<Time> {
+        self.attempts
+            .iter()
+            .filter(|attempt| attempt.is_finished())
+            .filter_map(|attempt| attempt.trusted_split(index))
+            .chain(self.segments[index].legacy_history.iter().copied())
+            .reduce(Time::min)
+    }
+
+    pub(crate) fn recompute_best_segment(&self, index: usize) -> Option<Time> {
+        self.attempts
+            .iter()
+            .filter_map(|attempt| attempt.segment_time(index))
+            .reduce(Time::min)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture;
+    use crate::{MockTime, ResetPolicy, Segment, Timer};
+
+    fn secs(secs: u64) -> Time {
+        Time::from(Duration::from_secs(secs))
+    }
+
+    #[test]
+    fn recompute() {
+        let attempts =
+            [[2, 5], [3, 4]].map(|splits| fixture::attempt(0, &splits.map(fixture::secs)));
+        // Edited by hand to a first split nobody ever made.
+        let segments = vec![
+            Segment::load("a".to_string(), Some(secs(1)), Some(secs(1))),
+            Segment::load("b".to_string(), Some(secs(4)), Some(secs(1))),
+        ];
+        let mut run = Run::load("test".to_string(), segments, attempts.to_vec());
+        let method = TimingMethod::RealTime;
+        run.set_personal_best(
+            method,
+            vec![Some(Duration::from_secs(1)), Some(Duration::from_secs(4))],
+        );
+        run.set_personal_best(
+            TimingMethod::GameTime,
+            vec![Some(Duration::from_secs(3)), Some(Duration::from_secs(4))],
+        );
+
+        let changes = run.recompute_bests();
+        assert_eq!(
+            changes,
+            [
+                BestChange::BestTime {
+                    segment: 0,
+                    stored: Some(secs(1)),
+                    recomputed: Some(secs(2)),
+                },
+                BestChange::BestSegment {
+                    segment: 0,
+                    stored: Some(secs(1)),
+                    recomputed: Some(secs(2)),
+                },
+                BestChange::PersonalBest {
+                    method,
+                    stored: vec![Some(Duration::from_secs(1)), Some(Duration::from_secs(4))],
+                    recomputed: vec![Some(Duration::from_secs(3)), Some(Duration::from_secs(4))],
+                },
+            ]
+        );
+        assert!(run.recompute_bests().is_empty());
+    }
+
+    #[test]
+    fn saved_attempts_unchanged() {
+        let mut run = Run::new(
+            "test".to_string(),
+            vec![Segment::new("a"), Segment::new("b")],
+        );
+        for splits in [&[2.0][..], &[3.0, 5.0], &[1.0], &[2.5, 6.0]] {
+            let mut timer = Timer::<MockTime>::new(run.len());
+            timer.start();
+            let mut last = 0.0;
+            for split in splits {
+                MockTime::step_secs(split - last);
+                timer.split();
+                last = *split;
+            }
+            run.save_attempt(&timer, ResetPolicy::Always.decision().unwrap());
+        }
+        assert_eq!(run.attempts().len(), 4);
+        assert!(run.recompute_bests().is_empty());
+    }
+
+    #[test]
+    fn added_attempts_unchanged() {
+        let mut run = fixture::run(
+            &["a", "b"],
+            [
+                [fixture::secs(2), None],
+                [fixture::secs(3), fixture::secs(5)],
+                [fixture::secs(1), None],
+            ],
+        );
+        assert_eq!(run.segments()[0].best_time(), Some(secs(3)));
+        assert!(run.recompute_bests().is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+use std::sync::mpsc::Receiver;
+
+use crate::{
+    event::TimerEvent,
+    reset::ResetDecision,
+    run::Run,
+    time::{TimeProvider, TimingMethod},
+    timer::Timer,
+};
+
+/// Everything a frontend can ask of the timer, independent of how it was asked for.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TimerCommand {
+    StartOrSplit,
+    Start,
+    Split,
+    Skip,
+    Undo,
+    Redo,
+    Pause,
+    Resume,
+    TogglePause,
+    PauseGameTime,
+    ResumeGameTime,
+    ToggleGameTimePause,
+    /// Resets the timer, keeping what `save` allows of the attempt.
+    Reset {
+        save: ResetDecision,
+    },
+    SwitchComparison,
+    ToggleTimingMethod,
+}
+
+/// Applies commands to a timer and its run, and tracks the comparison and timing
+/// method they are viewed with.
+pub struct CommandExecutor {
+    events: Receiver<TimerEvent>,
+    comparisons: usize,
+    comparison: usize,
+    timing_method: TimingMethod,
+}
+
+impl CommandExecutor {
+    /// An executor for commands to `timer`, cycling through `comparisons`
+    /// comparisons.
+    pub fn new<T: TimeProvider>(timer: &mut Timer<T>, comparisons: usize) -> Self {
+        Self {
+            events: timer.channel(),
+            comparisons,
+            comparison: 0,
+            timing_method: TimingMethod::default(),
+        }
+    }
+
+    /// The index of the selected comparison.
+    pub fn comparison(&self) -> usize {
+        self.comparison
+    }
+
+    pub fn timing_method(&self) -> TimingMethod {
+        self.timing_method
+    }
+
+    /// Applies `command` and returns the events the timer emitted because of it.
+    /// Events from calls to the timer made outside of the executor are dropped.
+    pub fn execute<T: TimeProvider>(
+        &mut self,
+        command: TimerCommand,
+        timer: &mut Timer<T>,
+        run: &mut Run,
+    ) -> Vec<TimerEvent> {
+        self.events.try_iter().for_each(drop);
+        match command {
+            TimerCommand::StartOrSplit => timer.start_split_or_unpause(),
+            TimerCommand::Start => timer.start(),
+            TimerCommand::Split => timer.split(),
+            TimerCommand::Skip => timer.skip(),
+            TimerCommand::Undo => timer.undo(),
+            TimerCommand::Redo => timer.redo(),
+            TimerCommand::Pause => timer.pause(),
+            TimerCommand::Resume => timer.unpause(),
+            TimerCommand::TogglePause => timer.toggle_pause(),
+            TimerCommand::PauseGameTime => timer.pause_game_time(),
+            TimerCommand::ResumeGameTime => timer.resume_game_time(),
+            TimerCommand::ToggleGameTimePause => timer.toggle_game_time_pause(),
+            TimerCommand::Reset { save } => {
+                run.save_attempt(timer, save);
+                timer.reset();
+            }
+            TimerCommand::SwitchComparison => {
+                self.comparison = (self.comparison + 1) % self.comparisons.max(1);
+            }
+            TimerCommand::ToggleTimingMethod => self.timing_method = self.timing_method.toggle(),
+        }
+        self.events.try_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MockTime, ResetPolicy, Segment, Split};
+
+    #[test]
+    fn execute() {
+        let mut run = Run::new("test".to_string(), vec![Segment::new("a")]);
+        let mut timer = Timer::<MockTime>::new(run.len());
+        let mut executor = CommandExecutor::new(&mut timer, 2);
+
+        let events = executor.execute(TimerCommand::StartOrSplit, &mut timer, &mut run);
+        assert_eq!(events, vec![TimerEvent::Started]);
+        MockTime::step_secs(1.0);
+        let events = executor.execute(TimerCommand::StartOrSplit, &mut timer, &mut run);
+        assert!(matches!(
+            events[..],
+            [TimerEvent::Split { index: 0, .. }, TimerEvent::Finished]
+        ));
+        assert!(matches!(timer.splits(), [Split::Split(_)]));
+
+        // Only the events of the command itself are returned.
+        timer.undo();
+        let events = executor.execute(TimerCommand::Pause, &mut timer, &mut run);
+        assert_eq!(events, vec![TimerEvent::Paused]);
+
+        let save = ResetPolicy::Always.decision().unwrap();
+        let events = executor.execute(TimerCommand::Reset { save }, &mut timer, &mut run);
+        assert_eq!(events, vec![TimerEvent::Reset]);
+        assert_eq!(run.attempts().len(), 1);
+
+        for command in [
+            TimerCommand::SwitchComparison,
+            TimerCommand::ToggleTimingMethod,
+        ] {
+            assert!(executor.execute(command, &mut timer, &mut run).is_empty());
+        }
+        assert_eq!(executor.comparison(), 1);
+        assert_eq!(executor.timing_method(), TimingMethod::GameTime);
+    }
+}
@@ -0,0 +1,262 @@
+use std::time::Duration;
+
+use crate::{run::Run, time::TimingMethod};
+
+/// Generates a target split time for every segment of a run.
+pub trait Comparison {
+    fn name(&self) -> &str;
+
+    /// The split time to compare against for every segment, `None` where there is
+    /// nothing to compare against.
+    fn splits(&self, run: &Run, method: TimingMethod) -> Vec<Option<Duration>>;
+}
+
+/// Every built-in comparison, personal best first.
+pub fn comparisons() -> Vec<Box<dyn Comparison>> {
+    vec![
+        Box::new(PersonalBest),
+        Box::new(BestSegments),
+        Box::new(BalancedPb),
+        Box::new(AverageSegments),
+        Box::new(MedianSegments),
+        Box::new(WorstSegments),
+        Box::new(LatestRun),
+    ]
+}
+
+pub struct PersonalBest;
+
+impl Comparison for PersonalBest {
+    fn name(&self) -> &str {
+        "Personal Best"
+    }
+
+    fn splits(&self, run: &Run, method: TimingMethod) -> Vec<Option<Duration>> {
+        let mut splits = run.personal_best(method).to_vec();
+        splits.resize(run.len(), None);
+        splits
+    }
+}
+
+/// The fastest time every split has been reached in, combining the best segments.
+pub struct BestSegments;
+
+impl Comparison for BestSegments {
+    fn name(&self) -> &str {
+        "Best Segments"
+    }
+
+    fn splits(&self, run: &Run, method: TimingMethod) -> Vec<Option<Duration>> {
+        run.best_splits(method)
+    }
+}
+
+pub struct AverageSegments;
+
+impl Comparison for AverageSegments {
+    fn name(&self) -> &str {
+        "Average Segments"
+    }
+
+    fn splits(&self, run: &Run, method: TimingMethod) -> Vec<Option<Duration>> {
+        segment_splits(run, method, |times| {
+            Some(times.iter().sum::<Duration>() / times.len() as u32)
+        })
+    }
+}
+
+pub struct MedianSegments;
+
+impl Comparison for MedianSegments {
+    fn name(&self) -> &str {
+        "Median Segments"
+    }
+
+    fn splits(&self, run: &Run, method: TimingMethod) -> Vec<Option<Duration>> {
+        segment_splits(run, method, |times| percentile(times, 0.5))
+    }
+}
+
+pub struct WorstSegments;
+
+impl Comparison for WorstSegments {
+    fn name(&self) -> &str {
+        "Worst Segments"
+    }
+
+    fn splits(&self, run: &Run, method: TimingMethod) -> Vec<Option<Duration>> {
+        segment_splits(run, method, |times| times.last().copied())
+    }
+}
+
+/// The splits of the most recent attempt, finished or not.
+pub struct LatestRun;
+
+impl Comparison for LatestRun {
+    fn name(&self) -> &str {
+        "Latest Run"
+    }
+
+    fn splits(&self, run: &Run, method: TimingMethod) -> Vec<Option<Duration>> {
+        match run.attempts().last() {
+            Some(attempt) => attempt
+                .splits()
+                .iter()
+                .map(|split| split.map(|time| time[method]))
+                .collect(),
+            None => vec![None; run.len()],
+        }
+    }
+}
+
+/// Splits that add up to the personal best, with every segment taken at the same
+/// percentile of its history. Shows where the personal best pace is comparatively
+/// easy or hard.
+pub struct BalancedPb;
+
+impl Comparison for BalancedPb {
+    fn name(&self) -> &str {
+        "Balanced PB"
+    }
+
+    fn splits(&self, run: &Run, method: TimingMethod) -> Vec<Option<Duration>> {
+        let Some(target) = run.personal_best_time(method) else {
+            return vec![None; run.len()];
+        };
+        let times = sorted_segment_times(run, method);
+        if times.iter().any(Vec::is_empty) {
+            return vec![None; run.len()];
+        }
+        let total =
+            |p: f64| -> Duration { times.iter().filter_map(|times| percentile(times, p)).sum() };
+        let (mut low, mut high) = (0.0, 1.0);
+        for _ in 0..32 {
+            let mid = (low + high) / 2.0;
+            if total(mid) < target {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        cumulative(times.iter().map(|times| percentile(times, low)))
+    }
+}
+
+fn sorted_segment_times(run: &Run, method: TimingMethod) -> Vec<Vec<Duration>> {
+    (0..run.len())
+        .map(|index| {
+            let mut times: Vec<_> = run.segment_times(index, method).collect();
+            times.sort();
+            times
+        })
+        .collect()
+}
+
+/// Sums a statistic of every segment's sorted history into split times.
+fn segment_splits<F>(run: &Run, method: TimingMethod, statistic: F) -> Vec<Option<Duration>>
+where
+    F: Fn(&[Duration]) -> Option<Duration>,
+{
+    cumulative(
+        sorted_segment_times(run, method)
+            .iter()
+            .map(|times| match times.is_empty() {
+                true => None,
+                false => statistic(times),
+            }),
+    )
+}
+
+/// Split times from segment times. Every split after a segment without a time is
+/// unknown as well.
+fn cumulative(segments: impl Iterator<Item = Option<Duration>>) -> Vec<Option<Duration>> {
+    segments
+        .scan(Some(Duration::ZERO), |total, segment| {
+            *total = total.zip(segment).map(|(total, segment)| total + segment);
+            Some(*total)
+        })
+        .collect()
+}
+
+/// The `p`-th percentile of sorted `times`, interpolating between neighbours.
+pub(crate) fn percentile(times: &[Duration], p: f64) -> Option<Duration> {
+    let last = times.len().checked_sub(1)?;
+    let rank = p.clamp(0.0, 1.0) * last as f64;
+    let (below, above) = (rank.floor() as usize, rank.ceil() as usize);
+    let fraction = rank - below as f64;
+    Some(times[below].mul_f64(1.0 - fraction) + times[above].mul_f64(fraction))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::fixture::{self, secs};
+    use crate::Segment;
+
+    fn durations(secs: &[u64]) -> Vec<Option<Duration>> {
+        secs.iter().map(|s| Some(Duration::from_secs(*s))).collect()
+    }
+
+    fn millis(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    fn run() -> Run {
+        fixture::run(
+            &["a", "b"],
+            [
+                [secs(4), secs(10)],
+                [secs(2), secs(8)],
+                [secs(3), secs(6)],
+                [secs(6), None],
+            ],
+        )
+    }
+
+    #[test]
+    fn generators() {
+        let run = run();
+        let method = TimingMethod::RealTime;
+        assert_eq!(PersonalBest.splits(&run, method), durations(&[3, 6]));
+        assert_eq!(BestSegments.splits(&run, method), durations(&[2, 5]));
+        assert_eq!(
+            AverageSegments.splits(&run, method),
+            vec![Some(millis(3750)), Some(millis(3750 + 5000))]
+        );
+        assert_eq!(
+            MedianSegments.splits(&run, method),
+            vec![Some(millis(3500)), Some(millis(3500 + 6000))]
+        );
+        assert_eq!(WorstSegments.splits(&run, method), durations(&[6, 6 + 6]));
+        assert_eq!(
+            LatestRun.splits(&run, method),
+            vec![Some(Duration::from_secs(6)), None]
+        );
+    }
+
+    #[test]
+    fn balanced_pb_adds_up_to_personal_best() {
+        let run = run();
+        let splits = BalancedPb.splits(&run, TimingMethod::RealTime);
+        let last = splits[1].unwrap().as_secs_f64();
+        assert!((last - 6.0).abs() < 1e-3, "{last}");
+    }
+
+    #[test]
+    fn missing_history() {
+        let run = Run::new("test".to_string(), vec![Segment::new("a")]);
+        for comparison in comparisons() {
+            assert_eq!(comparison.splits(&run, TimingMethod::RealTime), vec![None]);
+        }
+    }
+
+    #[test]
+    fn percentiles() {
+        let times = [Duration::from_secs(1), Duration::from_secs(3)];
+        assert_eq!(percentile(&times, 0.5), Some(Duration::from_secs(2)));
+        assert_eq!(percentile(&times, 1.0), Some(Duration::from_secs(3)));
+        assert_eq!(percentile(&[], 0.5), None);
+    }
+}
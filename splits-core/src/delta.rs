@@ -0,0 +1,204 @@
+use std::time::Duration;
+
+use crate::{
+    run::Run,
+    time::{SignedDuration, TimeProvider, TimingMethod},
+    timer::{Split, Timer, TimerState},
+};
+
+/// How a split compares to the comparison, using LiveSplit's semantics.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Pace {
+    AheadGaining,
+    AheadLosing,
+    BehindGaining,
+    BehindLosing,
+    /// The segment beat the best segment.
+    Gold,
+}
+
+impl Pace {
+    fn new(delta: SignedDuration, segment_delta: Option<SignedDuration>) -> Self {
+        let ahead = delta <= SignedDuration::ZERO;
+        // Without a segment delta, being ahead counts as gaining and behind as losing.
+        let gaining = segment_delta.map_or(ahead, |delta| delta <= SignedDuration::ZERO);
+        match (ahead, gaining) {
+            (true, true) => Pace::AheadGaining,
+            (true, false) => Pace::AheadLosing,
+            (false, true) => Pace::BehindGaining,
+            (false, false) => Pace::BehindLosing,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SplitDelta {
+    pub index: usize,
+    /// The split time minus the comparison's split time.
+    pub delta: Option<SignedDuration>,
+    /// The segment time minus the comparison's segment time.
+    pub segment_delta: Option<SignedDuration>,
+    pub pace: Option<Pace>,
+    /// Whether this is the segment currently being run, rather than a completed split.
+    pub live: bool,
+}
+
+impl Run {
+    /// The delta of every split of the timer's attempt so far, followed by the live
+    /// segment while the timer is running.
+    pub fn deltas<T: TimeProvider>(
+        &self,
+        timer: &Timer<T>,
+        comparison: &[Option<Duration>],
+        method: TimingMethod,
+    ) -> Vec<SplitDelta> {
+        let comparison_split = |index: usize| comparison.get(index).copied().flatten();
+        let comparison_segment = |index: usize| {
+            let previous = match index {
+                0 => Some(Duration::ZERO),
+                _ => comparison_split(index - 1),
+            };
+            Some(SignedDuration::difference(
+                comparison_split(index)?,
+                previous?,
+            ))
+        };
+        let previous_split = |index: usize| match index {
+            0 => Some(Duration::ZERO),
+            _ => match timer.splits()[index - 1] {
+                Split::Split(time) => Some(time[method]),
+                Split::Skipped => None,
+            },
+        };
+
+        let mut deltas: Vec<SplitDelta> = timer
+            .splits()
+            .iter()
+            .enumerate()
+            .map(|(index, split)| {
+                let Split::Split(time) = split else {
+                    return SplitDelta {
+                        index,
+                        delta: None,
+                        segment_delta: None,
+                        pace: None,
+                        live: false,
+                    };
+                };
+                let time = time[method];
+                let segment_time = previous_split(index)
+                    .map(|previous| SignedDuration::difference(time, previous));
+                let delta = comparison_split(index)
+                    .map(|comparison| SignedDuration::difference(time, comparison));
+                let segment_delta = segment_time
+                    .zip(comparison_segment(index))
+                    .map(|(segment, comparison)| segment - comparison);
+                let gold = segment_time
+                    .zip(self.segments()[index].best_segment())
+                    .is_some_and(|(segment, best)| segment < SignedDuration::from(best[method]));
+                let pace = match gold {
+                    true => Some(Pace::Gold),
+                    false => delta.map(|delta| Pace::new(delta, segment_delta)),
+                };
+                SplitDelta {
+                    index,
+                    delta,
+                    segment_delta,
+                    pace,
+                    live: false,
+                }
+            })
+            .collect();
+
+        if let TimerState::Running | TimerState::Paused(_) = timer.state() {
+            let index = timer.splits().len();
+            if index < self.len() {
+                let time = timer.current_time()[method];
+                let delta = comparison_split(index)
+                    .map(|comparison| SignedDuration::difference(time, comparison));
+                let segment_delta = previous_split(index).zip(comparison_segment(index)).map(
+                    |(previous, comparison)| {
+                        SignedDuration::difference(time, previous) - comparison
+                    },
+                );
+                deltas.push(SplitDelta {
+                    index,
+                    delta,
+                    segment_delta,
+                    pace: delta.map(|delta| Pace::new(delta, segment_delta)),
+                    live: true,
+                });
+            }
+        }
+        deltas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{fixture, MockTime};
+
+    fn secs(secs: f64) -> SignedDuration {
+        SignedDuration::from_secs_f64(secs)
+    }
+
+    fn run() -> Run {
+        fixture::run(&["a", "b", "c", "d"], [[2, 4, 6, 8].map(fixture::secs)])
+    }
+
+    fn comparison() -> Vec<Option<Duration>> {
+        [3, 6, 9, 12].map(|s| Some(Duration::from_secs(s))).to_vec()
+    }
+
+    #[test]
+    fn deltas() {
+        let run = run();
+        let mut timer = Timer::<MockTime>::new(run.len());
+        timer.start();
+        // Ahead and gaining, but not a gold.
+        MockTime::step_secs(2.5);
+        timer.split();
+        // Ahead but losing.
+        MockTime::step_secs(3.25);
+        timer.split();
+        // Behind and losing.
+        MockTime::step_secs(4.0);
+        timer.split();
+        MockTime::step_secs(1.0);
+        let deltas = run.deltas(&timer, &comparison(), TimingMethod::RealTime);
+        assert_eq!(
+            deltas.iter().map(|delta| delta.pace).collect::<Vec<_>>(),
+            vec![
+                Some(Pace::AheadGaining),
+                Some(Pace::AheadLosing),
+                Some(Pace::BehindLosing),
+                Some(Pace::AheadGaining),
+            ]
+        );
+        assert_eq!(deltas[2].delta, Some(secs(0.75)));
+        assert_eq!(deltas[2].segment_delta, Some(secs(1.0)));
+        assert!(deltas[3].live);
+        assert_eq!(deltas[3].delta, Some(secs(-1.25)));
+        assert_eq!(deltas[3].segment_delta, Some(secs(-2.0)));
+    }
+
+    #[test]
+    fn gold_and_skipped() {
+        let run = run();
+        let mut timer = Timer::<MockTime>::new(run.len());
+        timer.start();
+        MockTime::step_secs(1.0);
+        timer.split();
+        timer.skip();
+        MockTime::step_secs(5.0);
+        timer.split();
+        let deltas = run.deltas(&timer, &comparison(), TimingMethod::RealTime);
+        assert_eq!(deltas[0].pace, Some(Pace::Gold));
+        assert_eq!(deltas[1].delta, None);
+        assert_eq!(deltas[2].segment_delta, None);
+        assert_eq!(deltas[2].pace, Some(Pace::AheadGaining));
+    }
+}
@@ -0,0 +1,316 @@
+use std::{ops::Add, time::Duration};
+
+use crate::{group::SegmentGroup, run::Run, segment::Segment, time::Time};
+
+#[derive(Debug, PartialEq, Eq, onlyerror::Error)]
+pub enum EditError {
+    #[error("the last segment has no segment after it to merge with")]
+    MergeLast,
+}
+
+/// Restructuring the route. Every edit is applied to the stored attempts and the
+/// personal best as well, so comparisons built from them stay aligned with the
+/// segments.
+impl Run {
+    /// Inserts a new segment at `index`. Earlier attempts never split it, so the
+    /// segment after it loses its best segment and practice, which covered both.
+    /// Appending a segment leaves the attempts and the personal best without a
+    /// final time, and finished attempts count as reset in the new segment.
+    pub fn insert_segment(&mut self, index: usize, segment: Segment) {
+        let appended = index == self.len();
+        let joins = self
+            .groups
+            .iter()
+            .position(|group| group.range().start < index && index < group.range().end);
+        self.segments.insert(index, segment);
+        for attempt in &mut self.attempts {
+            let was_finished = attempt.is_finished();
+            attempt.splits_mut().insert(index, None);
+            attempt.remap_segments(|i| if i >= index { i + 1 } else { i });
+            if appended && was_finished {
+                attempt.set_reset_segment(Some(index));
+            }
+        }
+        self.personal_best.edit(|splits| splits.insert(index, None));
+        if let Some(next) = self.segments.get_mut(index + 1) {
+            next.best_segment = None;
+            next.practice_history.clear();
+        }
+        self.insert_into_groups(index, joins);
+    }
+
+    /// Removes the segment at `index`. Its time counts towards the following
+    /// segment, or ends the run early if it was the last one.
+    pub fn remove_segment(&mut self, index: usize) -> Segment {
+        let joins_next = index + 1 < self.len();
+        let combined_best = self
+            .segments
+            .get(index + 1)
+            .and_then(|next| Some(self.segments[index].best_segment? + next.best_segment?));
+        let segment = self.segments.remove(index);
+        for attempt in &mut self.attempts {
+            attempt.splits_mut().remove(index);
+            attempt.remap_segments(|i| if i > index { i - 1 } else { i });
+        }
+        self.personal_best.edit(|splits| {
+            splits.remove(index);
+        });
+        if joins_next {
+            // Attempts that split both sides of the removed split know the combined
+            // segment exactly, the sum of both bests is only a fallback.
+            let best_segment = self.recompute_best_segment(index).or(combined_best);
+            let joined = &mut self.segments[index];
+            joined.best_segment = best_segment;
+            joined.practice_history.clear();
+        } else {
+            self.recompute_personal_best();
+        }
+        self.remove_from_groups(index);
+        segment
+    }
+
+    /// Merges the segment at `index` with the one after it, keeping the title of the
+    /// first.
+    pub fn merge_segments(&mut self, index: usize) -> Result<(), EditError> {
+        if index + 1 >= self.len() {
+            return Err(EditError::MergeLast);
+        }
+        let title = self.segments[index].title.clone();
+        self.remove_segment(index);
+        self.segments[index].title = title;
+        Ok(())
+    }
+
+    /// Splits the segment at `index` in two. The first half keeps the title and the
+    /// second, named `title`, keeps the split.
+    pub fn split_segment(&mut self, index: usize, title: &str) {
+        let joins = self.groups.iter().position(|group| group.contains(index));
+        let first = &mut self.segments[index];
+        let mut second = Segment::load(title.to_string(), first.best_time.take(), None);
+        second.legacy_history = std::mem::take(&mut first.legacy_history);
+        first.best_segment = None;
+        first.practice_history.clear();
+        self.segments.insert(index + 1, second);
+        for attempt in &mut self.attempts {
+            attempt.splits_mut().insert(index, None);
+            // Resets and exclusions in the split segment stay with its first half.
+            attempt.remap_segments(|i| if i > index { i + 1 } else { i });
+        }
+        self.personal_best.edit(|splits| splits.insert(index, None));
+        self.insert_into_groups(index + 1, joins);
+    }
+
+    pub fn rename_segment(&mut self, index: usize, title: &str) {
+        self.segments[index].title = title.to_string();
+    }
+
+    /// Moves the segment at `from` to `to`, reordering the recorded segment times to
+    /// match.
+    pub fn move_segment(&mut self, from: usize, to: usize) {
+        if from == to {
+            return;
+        }
+        let segment = self.segments.remove(from);
+        self.segments.insert(to, segment);
+        for attempt in &mut self.attempts {
+            move_split(attempt.splits_mut(), from, to);
+            attempt.remap_segments(|i| match i {
+                i if i == from => to,
+                i if from < i && i <= to => i - 1,
+                i if to <= i && i < from => i + 1,
+                i => i,
+            });
+        }
+        self.personal_best
+            .edit(|splits| move_split(splits, from, to));
+        // Every split before the end of the moved stretch now follows a different
+        // set of segments, which legacy split times can't be adjusted to.
+        for index in from.min(to)..from.max(to) {
+            self.segments[index].legacy_history.clear();
+            self.segments[index].best_time = self.recompute_best_time(index);
+        }
+        let alone = self
+            .groups
+            .iter()
+            .position(|group| group.range() == (from..from + 1));
+        let group = alone.map(|position| self.groups.remove(position));
+        self.remove_from_groups(from);
+        let joins = self
+            .groups
+            .iter()
+            .position(|group| group.range().start < to && to < group.range().end);
+        self.insert_into_groups(to, joins);
+        // A group of only the moved segment moves along, unless the segment ends up
+        // inside another group.
+        if let Some(group) = group.filter(|_| joins.is_none()) {
+            self.add_group(SegmentGroup::new(group.title(), to..to + 1))
+                .expect("the moved segment is outside of every group");
+        }
+    }
+}
+
+/// A split time of an attempt or the personal best.
+trait SplitTime: Copy + Add<Output = Self> {
+    const ZERO: Self;
+
+    fn checked_sub(self, other: Self) -> Option<Self>;
+}
+
+impl SplitTime for Time {
+    const ZERO: Self = Time::ZERO;
+
+    fn checked_sub(self, other: Self) -> Option<Self> {
+        Time::checked_sub(self, other)
+    }
+}
+
+impl SplitTime for Duration {
+    const ZERO: Self = Duration::ZERO;
+
+    fn checked_sub(self, other: Self) -> Option<Self> {
+        Duration::checked_sub(self, other)
+    }
+}
+
+/// Moves the segment ending at split `from` to `to` by reordering the segment times
+/// in between. The splits in between become unknown if any of those segment times
+/// is.
+fn move_split<T: SplitTime>(splits: &mut [Option<T>], from: usize, to: usize) {
+    let (start, end) = (from.min(to), from.max(to));
+    let previous = |index: usize| match index {
+        0 => Some(T::ZERO),
+        _ => splits[index - 1],
+    };
+    let segments: Option<Vec<T>> = (start..=end)
+        .map(|index| splits[index]?.checked_sub(previous(index)?))
+        .collect();
+    let (Some(mut segments), Some(mut total)) = (segments, previous(start)) else {
+        splits[start..end].fill(None);
+        return;
+    };
+    let segment = segments.remove(from - start);
+    segments.insert(to - start, segment);
+    for (split, segment) in splits[start..=end].iter_mut().zip(segments) {
+        total = total + segment;
+        *split = Some(total);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::{self, attempt, secs};
+    use crate::{SegmentGroup, TimingMethod};
+
+    fn run() -> Run {
+        let mut run = fixture::run(
+            &["a", "b", "c", "d"],
+            [
+                [secs(1), secs(3), secs(6), secs(10)],
+                [secs(2), None, secs(4), secs(12)],
+            ],
+        );
+        run.add_group(SegmentGroup::new("group", 1..3)).unwrap();
+        run
+    }
+
+    fn titles(run: &Run) -> Vec<&str> {
+        run.segments().iter().map(Segment::title).collect()
+    }
+
+    #[test]
+    fn insert_and_split() {
+        let mut run = run();
+        run.insert_segment(2, Segment::new("new"));
+        run.split_segment(0, "a2");
+        assert_eq!(titles(&run), vec!["a", "a2", "b", "new", "c", "d"]);
+        assert_eq!(
+            run.attempts()[0].splits(),
+            &[None, secs(1), secs(3), None, secs(6), secs(10)]
+        );
+        assert_eq!(run.segments()[0].best_time(), None);
+        assert_eq!(run.segments()[1].best_time(), secs(1));
+        assert_eq!(run.segments()[1].best_segment(), None);
+        // Its duration now spans the new segment as well.
+        assert_eq!(run.segments()[4].best_segment(), None);
+        assert_eq!(run.groups()[0].range(), 2..5);
+        assert_eq!(
+            run.personal_best_time(TimingMethod::RealTime),
+            Some(Duration::from_secs(10))
+        );
+    }
+
+    #[test]
+    fn remove_and_merge() {
+        let mut run = run();
+        run.merge_segments(1).unwrap();
+        assert_eq!(titles(&run), vec!["a", "b", "d"]);
+        assert_eq!(run.attempts()[1].splits(), &[secs(2), secs(4), secs(12)]);
+        // Recomputed from the attempts rather than adding up both best segments.
+        assert_eq!(run.segments()[1].best_segment(), secs(2));
+        assert_eq!(run.groups()[0].range(), 1..2);
+
+        let removed = run.remove_segment(2);
+        assert_eq!(removed.title(), "d");
+        assert_eq!(
+            run.personal_best(TimingMethod::RealTime),
+            &[Some(Duration::from_secs(2)), Some(Duration::from_secs(4))]
+        );
+        run.remove_segment(1);
+        assert!(run.groups().is_empty());
+    }
+
+    #[test]
+    fn remove_last() {
+        let mut run = run();
+        let mut reset = attempt(2, &[secs(1), secs(3), secs(6), None]);
+        reset.exclude(3);
+        run.add_attempt(reset);
+        run.remove_segment(3);
+        // Reset in the removed segment, so it now ends in the last split.
+        assert_eq!(run.attempts()[2].reset_segment(), None);
+        assert!(run.attempts()[2].excluded().is_empty());
+        assert_eq!(run.survival()[2].survival, 1.0);
+    }
+
+    #[test]
+    fn merge_last() {
+        let mut run = run();
+        assert_eq!(run.merge_segments(3), Err(EditError::MergeLast));
+        assert_eq!(run.len(), 4);
+    }
+
+    #[test]
+    fn append() {
+        let mut run = run();
+        run.insert_segment(4, Segment::new("e"));
+        assert!(!run.attempts()[0].is_finished());
+        assert_eq!(run.attempts()[0].reset_segment(), Some(4));
+        assert_eq!(run.survival()[4].reset, 2);
+    }
+
+    #[test]
+    fn move_segment() {
+        let mut run = run();
+        run.move_segment(0, 2);
+        assert_eq!(titles(&run), vec!["b", "c", "a", "d"]);
+        assert_eq!(
+            run.attempts()[0].splits(),
+            &[secs(2), secs(5), secs(6), secs(10)]
+        );
+        assert_eq!(run.attempts()[1].splits(), &[None, None, secs(4), secs(12)]);
+        assert_eq!(run.segments()[0].best_time(), secs(2));
+        assert_eq!(run.segments()[2].best_segment(), secs(1));
+        assert_eq!(
+            run.personal_best(TimingMethod::RealTime)[1],
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(run.groups()[0].range(), 0..2);
+
+        // A group of only the moved segment goes along with it.
+        run.add_group(SegmentGroup::new("alone", 3..4)).unwrap();
+        run.move_segment(3, 0);
+        assert_eq!(run.groups()[0], SegmentGroup::new("alone", 0..1));
+        assert_eq!(run.groups()[1].range(), 1..3);
+    }
+}
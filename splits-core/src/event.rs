@@ -0,0 +1,51 @@
+use std::{
+    sync::mpsc::{self, Receiver, Sender},
+    time::Duration,
+};
+
+use crate::time::Time;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TimerEvent {
+    Started,
+    Split { index: usize, time: Time },
+    Skipped { index: usize },
+    Undone { index: usize },
+    Redone { index: usize },
+    SplitEdited { index: usize },
+    Paused,
+    Resumed,
+    GameTimePaused,
+    GameTimeResumed,
+    GameTimeSet { time: Duration },
+    Reset,
+    Finished,
+}
+
+type Callback = Box<dyn FnMut(&TimerEvent) + Send>;
+
+#[derive(Default)]
+pub(crate) struct Subscribers {
+    callbacks: Vec<Callback>,
+    senders: Vec<Sender<TimerEvent>>,
+}
+
+impl Subscribers {
+    pub fn subscribe<F: FnMut(&TimerEvent) + Send + 'static>(&mut self, callback: F) {
+        self.callbacks.push(Box::new(callback));
+    }
+
+    pub fn channel(&mut self) -> Receiver<TimerEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.senders.push(sender);
+        receiver
+    }
+
+    pub fn emit(&mut self, event: TimerEvent) {
+        for callback in &mut self.callbacks {
+            callback(&event);
+        }
+        // Receivers that have been dropped are unsubscribed.
+        self.senders.retain(|sender| sender.send(event).is_ok());
+    }
+}
@@ -0,0 +1,29 @@
+//! Runs and attempts shared by the tests of the other modules.
+
+use std::time::Duration;
+
+use crate::{Attempt, Run, Segment, Time};
+
+/// A split of `secs` seconds in both timing methods.
+pub fn secs(secs: u64) -> Option<Time> {
+    Some(Time::from(Duration::from_secs(secs)))
+}
+
+/// An attempt with the given splits and nothing else recorded.
+pub fn attempt(id: u32, splits: &[Option<Time>]) -> Attempt {
+    Attempt::new(id, None, None, Duration::ZERO, splits.to_vec())
+}
+
+/// A run with a segment for each title and an attempt for each list of splits,
+/// numbered from 0 and added with [`Run::add_attempt`].
+pub fn run<S: AsRef<[Option<Time>]>>(
+    titles: &[&str],
+    attempts: impl IntoIterator<Item = S>,
+) -> Run {
+    let segments = titles.iter().copied().map(Segment::new).collect();
+    let mut run = Run::new("test".to_string(), segments);
+    for (id, splits) in attempts.into_iter().enumerate() {
+        run.add_attempt(attempt(id as u32, splits.as_ref()));
+    }
+    run
+}
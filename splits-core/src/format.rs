@@ -0,0 +1,238 @@
+use std::time::Duration;
+
+use crate::time::SignedDuration;
+
+/// How a [`TimeFormatter`] lays out hours, minutes and seconds.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TimeStyle {
+    /// Always `h:mm:ss`.
+    Full,
+    /// Leaves out hours and minutes while they are zero, as in `5.40` or `1:05.40`.
+    #[default]
+    Compact,
+    /// Like [`TimeStyle::Compact`], but always signed, as in `+5.40` or `-1:05.40`.
+    Delta,
+}
+
+/// Formats times for display.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TimeFormatter {
+    style: TimeStyle,
+    decimals: u8,
+    minutes: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, onlyerror::Error)]
+pub enum ParseTimeError {
+    #[error("no time given")]
+    Empty,
+    #[error("`{0}` is not a number")]
+    InvalidNumber(String),
+    #[error("`{0}` has to be less than 60")]
+    OutOfRange(String),
+    #[error("`{0}` has more than hours, minutes and seconds")]
+    TooManyParts(String),
+}
+
+impl TimeFormatter {
+    /// A formatter for `style` showing hundredths of a second.
+    pub fn new(style: TimeStyle) -> Self {
+        Self {
+            style,
+            decimals: 2,
+            minutes: false,
+        }
+    }
+
+    /// Shows `decimals` digits of the fraction of a second, at most 3.
+    pub fn with_decimals(self, decimals: u8) -> Self {
+        Self {
+            decimals: decimals.min(3),
+            ..self
+        }
+    }
+
+    /// Shows minutes even while they are zero, as in `0:05.40`.
+    pub fn with_minutes(self, minutes: bool) -> Self {
+        Self { minutes, ..self }
+    }
+
+    pub fn style(&self) -> TimeStyle {
+        self.style
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    /// Formats `time`, truncating digits that aren't shown.
+    pub fn format(&self, time: impl Into<SignedDuration>) -> String {
+        let time = time.into();
+        let abs = time.abs();
+        let sign = match (time.is_negative(), self.style) {
+            (true, _) => "-",
+            (false, TimeStyle::Delta) => "+",
+            (false, _) => "",
+        };
+        let secs = abs.as_secs();
+        let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+        let mut formatted = match (self.style, hours, minutes) {
+            (TimeStyle::Full, ..) => format!("{sign}{hours}:{minutes:02}:{seconds:02}"),
+            (_, 0, 0) if !self.minutes => format!("{sign}{seconds}"),
+            (_, 0, _) => format!("{sign}{minutes}:{seconds:02}"),
+            _ => format!("{sign}{hours}:{minutes:02}:{seconds:02}"),
+        };
+        if self.decimals > 0 {
+            let fraction = abs.subsec_nanos() / 10u32.pow(9 - self.decimals as u32);
+            formatted.push_str(&format!(
+                ".{fraction:0width$}",
+                width = self.decimals as usize
+            ));
+        }
+        formatted
+    }
+}
+
+impl Default for TimeFormatter {
+    fn default() -> Self {
+        Self::new(TimeStyle::default())
+    }
+}
+
+/// Parses a time as written by people, such as `1:02:05.4`, `65.4` or `-0:01.35`.
+/// Seconds and minutes can only go past 59 while they are the largest unit given.
+pub fn parse_time(time: &str) -> Result<SignedDuration, ParseTimeError> {
+    let time = time.trim();
+    let (negative, unsigned) = match time.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, time.strip_prefix('+').unwrap_or(time)),
+    };
+    if unsigned.is_empty() {
+        return Err(ParseTimeError::Empty);
+    }
+    let parts: Vec<_> = unsigned.split(':').collect();
+    if parts.len() > 3 {
+        return Err(ParseTimeError::TooManyParts(time.to_string()));
+    }
+    let (units, seconds) = parts.split_at(parts.len() - 1);
+    let mut total = 0;
+    for (index, unit) in units.iter().enumerate() {
+        let value = parse_digits(unit)?;
+        if index > 0 && value >= 60 {
+            return Err(ParseTimeError::OutOfRange(unit.to_string()));
+        }
+        total = checked_units(total, value).ok_or_else(|| out_of_range(time))?;
+    }
+    let (whole, fraction) = seconds[0].split_once('.').unwrap_or((seconds[0], ""));
+    let whole_seconds = parse_digits(whole)?;
+    if !units.is_empty() && whole_seconds >= 60 {
+        return Err(ParseTimeError::OutOfRange(seconds[0].to_string()));
+    }
+    let nanos = match fraction {
+        "" => 0,
+        fraction if fraction.bytes().all(|b| b.is_ascii_digit()) => {
+            // Anything past nanoseconds is cut off.
+            let digits = &fraction[..fraction.len().min(9)];
+            parse_digits(digits)? as u32 * 10u32.pow(9 - digits.len() as u32)
+        }
+        fraction => return Err(ParseTimeError::InvalidNumber(fraction.to_string())),
+    };
+    let seconds = checked_units(total, whole_seconds).ok_or_else(|| out_of_range(time))?;
+    let duration = Duration::new(seconds, nanos);
+    Ok(match negative {
+        true => SignedDuration::negative(duration),
+        false => SignedDuration::from(duration),
+    })
+}
+
+/// `larger` units of 60 plus `value`, `None` on overflow.
+fn checked_units(larger: u64, value: u64) -> Option<u64> {
+    larger.checked_mul(60)?.checked_add(value)
+}
+
+fn out_of_range(time: &str) -> ParseTimeError {
+    ParseTimeError::OutOfRange(time.to_string())
+}
+
+fn parse_digits(digits: &str) -> Result<u64, ParseTimeError> {
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ParseTimeError::InvalidNumber(digits.to_string()));
+    }
+    digits
+        .parse()
+        .map_err(|_| ParseTimeError::InvalidNumber(digits.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn format() {
+        let compact = TimeFormatter::default();
+        assert_eq!(compact.format(millis(5_409)), "5.40");
+        assert_eq!(compact.format(millis(65_400)), "1:05.40");
+        assert_eq!(compact.format(millis(3_725_400)), "1:02:05.40");
+        assert_eq!(compact.with_decimals(0).format(millis(5_999)), "5");
+        assert_eq!(compact.with_decimals(5).format(millis(5_123)), "5.123");
+
+        let minutes = compact.with_minutes(true);
+        assert_eq!(minutes.format(millis(5_409)), "0:05.40");
+        assert_eq!(minutes.format(millis(3_725_400)), "1:02:05.40");
+
+        let full = TimeFormatter::new(TimeStyle::Full).with_decimals(1);
+        assert_eq!(full.format(millis(5_400)), "0:00:05.4");
+
+        let delta = TimeFormatter::new(TimeStyle::Delta);
+        assert_eq!(delta.format(millis(1_350)), "+1.35");
+        assert_eq!(
+            delta.format(SignedDuration::negative(millis(61_350))),
+            "-1:01.35"
+        );
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            parse_time("1:02:05.4"),
+            Ok(SignedDuration::from(millis(3_725_400)))
+        );
+        assert_eq!(parse_time("65.4"), Ok(SignedDuration::from(millis(65_400))));
+        assert_eq!(
+            parse_time("-0:01.35"),
+            Ok(SignedDuration::negative(millis(1_350)))
+        );
+        assert_eq!(
+            parse_time(" 90:00 "),
+            Ok(SignedDuration::from(millis(5_400_000)))
+        );
+        assert_eq!(parse_time(""), Err(ParseTimeError::Empty));
+        assert_eq!(
+            parse_time("1:60"),
+            Err(ParseTimeError::OutOfRange("60".to_string()))
+        );
+        assert_eq!(
+            parse_time("1.2.3"),
+            Err(ParseTimeError::InvalidNumber("2.3".to_string()))
+        );
+        assert!(matches!(
+            parse_time("1:2:3:4"),
+            Err(ParseTimeError::TooManyParts(_))
+        ));
+        assert!(matches!(
+            parse_time("999999999999999999:00:00"),
+            Err(ParseTimeError::OutOfRange(_))
+        ));
+    }
+
+    #[test]
+    fn round_trip() {
+        let formatter = TimeFormatter::new(TimeStyle::Delta).with_decimals(3);
+        let time = SignedDuration::negative(millis(3_725_123));
+        assert_eq!(parse_time(&formatter.format(time)), Ok(time));
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+
+use crate::run::Run;
+
+/// A game with a run for each of its categories.
+#[derive(Clone, Debug)]
+pub struct Game {
+    title: String,
+    categories: Vec<Category>,
+}
+
+/// A category's run together with the variables it was run under, such as the
+/// platform or region. The category is named by the title of its run, and the name
+/// alone tells it apart from the other categories of the game. Variants that only
+/// differ in their variables need names of their own, like `Any% (PC)`.
+#[derive(Clone, Debug)]
+pub struct Category {
+    run: Run,
+    variables: BTreeMap<String, String>,
+}
+
+#[derive(Debug, onlyerror::Error)]
+pub enum GameError {
+    #[error("category `{0}` already exists, whatever its variables")]
+    DuplicateCategory(String),
+}
+
+impl Game {
+    pub fn new(title: String) -> Self {
+        Self {
+            title,
+            categories: Vec::new(),
+        }
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn categories(&self) -> &[Category] {
+        &self.categories
+    }
+
+    pub fn category(&self, name: &str) -> Option<&Category> {
+        self.categories
+            .iter()
+            .find(|category| category.name() == name)
+    }
+
+    pub fn category_mut(&mut self, name: &str) -> Option<&mut Category> {
+        self.categories
+            .iter_mut()
+            .find(|category| category.name() == name)
+    }
+
+    /// Adds `category`, unless the game already has a category of the same name,
+    /// whatever its variables.
+    pub fn add_category(&mut self, category: Category) -> Result<(), GameError> {
+        if self.category(category.name()).is_some() {
+            return Err(GameError::DuplicateCategory(category.name().to_string()));
+        }
+        self.categories.push(category);
+        Ok(())
+    }
+
+    pub fn remove_category(&mut self, name: &str) -> Option<Category> {
+        let index = self
+            .categories
+            .iter()
+            .position(|category| category.name() == name)?;
+        Some(self.categories.remove(index))
+    }
+}
+
+impl Category {
+    pub fn new(run: Run) -> Self {
+        Self::load(run, BTreeMap::new())
+    }
+
+    pub fn load(run: Run, variables: BTreeMap<String, String>) -> Self {
+        Self { run, variables }
+    }
+
+    pub fn name(&self) -> &str {
+        self.run.title()
+    }
+
+    pub fn run(&self) -> &Run {
+        &self.run
+    }
+
+    pub fn run_mut(&mut self) -> &mut Run {
+        &mut self.run
+    }
+
+    pub fn variables(&self) -> &BTreeMap<String, String> {
+        &self.variables
+    }
+
+    pub fn variable(&self, key: &str) -> Option<&str> {
+        self.variables.get(key).map(String::as_str)
+    }
+
+    pub fn set_variable(&mut self, key: String, value: String) {
+        self.variables.insert(key, value);
+    }
+
+    pub fn remove_variable(&mut self, key: &str) -> Option<String> {
+        self.variables.remove(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Segment;
+
+    fn category(name: &str) -> Category {
+        Category::new(Run::new(name.to_string(), vec![Segment::new("a")]))
+    }
+
+    #[test]
+    fn categories() {
+        let mut game = Game::new("game".to_string());
+        game.add_category(category("Any%")).unwrap();
+        game.add_category(category("100%")).unwrap();
+        assert!(matches!(
+            game.add_category(category("Any%")),
+            Err(GameError::DuplicateCategory(_))
+        ));
+
+        game.category_mut("100%")
+            .unwrap()
+            .set_variable("platform".to_string(), "PC".to_string());
+        assert_eq!(
+            game.category("100%").unwrap().variable("platform"),
+            Some("PC")
+        );
+        assert_eq!(game.category("Any%").unwrap().variable("platform"), None);
+
+        assert!(game.remove_category("Any%").is_some());
+        assert_eq!(game.categories().len(), 1);
+    }
+}
@@ -0,0 +1,189 @@
+use std::{ops::Range, time::Duration};
+
+use crate::{run::Run, time::TimingMethod};
+
+/// A named parent of consecutive segments, such as a world made up of levels.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SegmentGroup {
+    title: String,
+    range: Range<usize>,
+}
+
+#[derive(Debug, onlyerror::Error)]
+pub enum GroupError {
+    #[error("segment group `{0}` contains no segments")]
+    Empty(String),
+    #[error("segment group `{0}` extends past the last segment")]
+    OutOfBounds(String),
+    #[error("segment group `{0}` overlaps another group")]
+    Overlapping(String),
+}
+
+impl SegmentGroup {
+    pub fn new(title: &str, range: Range<usize>) -> Self {
+        Self {
+            title: title.into(),
+            range,
+        }
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// The indices of the segments in the group.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        self.range.contains(&index)
+    }
+
+    /// The index of the segment whose split ends the group.
+    pub fn last(&self) -> usize {
+        self.range.end - 1
+    }
+
+    /// The group's split time, given the split times of every segment.
+    pub fn split_time(&self, splits: &[Option<Duration>]) -> Option<Duration> {
+        splits.get(self.last()).copied().flatten()
+    }
+
+    /// The time spent in the group, given the split times of every segment.
+    pub fn segment_time(&self, splits: &[Option<Duration>]) -> Option<Duration> {
+        let start = match self.range.start {
+            0 => Duration::ZERO,
+            start => splits.get(start - 1).copied().flatten()?,
+        };
+        self.split_time(splits)?.checked_sub(start)
+    }
+}
+
+impl Run {
+    pub fn groups(&self) -> &[SegmentGroup] {
+        &self.groups
+    }
+
+    pub fn add_group(&mut self, group: SegmentGroup) -> Result<(), GroupError> {
+        if group.range.is_empty() {
+            return Err(GroupError::Empty(group.title));
+        }
+        if group.range.end > self.len() {
+            return Err(GroupError::OutOfBounds(group.title));
+        }
+        if self
+            .groups
+            .iter()
+            .any(|other| other.range.start < group.range.end && group.range.start < other.range.end)
+        {
+            return Err(GroupError::Overlapping(group.title));
+        }
+        let position = self
+            .groups
+            .partition_point(|other| other.range.start < group.range.start);
+        self.groups.insert(position, group);
+        Ok(())
+    }
+
+    pub fn remove_group(&mut self, index: usize) -> SegmentGroup {
+        self.groups.remove(index)
+    }
+
+    /// The group the segment at `index` belongs to.
+    pub fn group_of(&self, index: usize) -> Option<&SegmentGroup> {
+        self.groups.iter().find(|group| group.contains(index))
+    }
+
+    /// Makes room for a segment inserted at `index`, which joins the group at
+    /// position `joins` in `groups`.
+    pub(crate) fn insert_into_groups(&mut self, index: usize, joins: Option<usize>) {
+        for (position, group) in self.groups.iter_mut().enumerate() {
+            if Some(position) == joins {
+                group.range.end += 1;
+            } else if group.range.start >= index {
+                group.range.start += 1;
+                group.range.end += 1;
+            }
+        }
+    }
+
+    /// Closes the gap left by the segment removed from `index`, dropping groups that
+    /// end up empty.
+    pub(crate) fn remove_from_groups(&mut self, index: usize) {
+        for group in &mut self.groups {
+            if group.contains(index) {
+                group.range.end -= 1;
+            } else if group.range.start > index {
+                group.range.start -= 1;
+                group.range.end -= 1;
+            }
+        }
+        self.groups.retain(|group| !group.range.is_empty());
+    }
+
+    /// The fastest the whole group has been completed in, combining best segments.
+    pub fn best_group_segment(
+        &self,
+        group: &SegmentGroup,
+        method: TimingMethod,
+    ) -> Option<Duration> {
+        self.best_range(group.range.start, group.range.end, method)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::fixture;
+
+    fn run() -> Run {
+        fixture::run(
+            &["1-1", "1-2", "2-1", "2-2", "boss"],
+            [[1, 3, 6, 8, 9], [2, 3, 5, 8, 10]].map(|splits| splits.map(fixture::secs)),
+        )
+    }
+
+    #[test]
+    fn add_group() {
+        let mut run = run();
+        run.add_group(SegmentGroup::new("World 2", 2..4)).unwrap();
+        run.add_group(SegmentGroup::new("World 1", 0..2)).unwrap();
+        assert!(matches!(
+            run.add_group(SegmentGroup::new("World 3", 3..5)),
+            Err(GroupError::Overlapping(_))
+        ));
+        assert!(matches!(
+            run.add_group(SegmentGroup::new("World 3", 4..6)),
+            Err(GroupError::OutOfBounds(_))
+        ));
+        assert!(matches!(
+            run.add_group(SegmentGroup::new("World 3", 4..4)),
+            Err(GroupError::Empty(_))
+        ));
+        assert_eq!(
+            run.groups()
+                .iter()
+                .map(SegmentGroup::title)
+                .collect::<Vec<_>>(),
+            vec!["World 1", "World 2"]
+        );
+        assert_eq!(run.group_of(3).map(SegmentGroup::title), Some("World 2"));
+        assert_eq!(run.group_of(4), None);
+    }
+
+    #[test]
+    fn group_times() {
+        let run = run();
+        let group = SegmentGroup::new("World 2", 2..4);
+        let splits = [1, 3, 6, 8, 9].map(|s| Some(Duration::from_secs(s)));
+        assert_eq!(group.split_time(&splits), Some(Duration::from_secs(8)));
+        assert_eq!(group.segment_time(&splits), Some(Duration::from_secs(5)));
+        assert_eq!(
+            run.best_group_segment(&group, TimingMethod::RealTime),
+            Some(Duration::from_secs(2 + 2))
+        );
+    }
+}
@@ -1,7 +1,47 @@
+mod attempt;
+mod bests;
+mod command;
+pub mod comparison;
+mod delta;
+mod edit;
+mod event;
+#[cfg(test)]
+mod fixture;
+mod format;
+mod game;
+mod group;
+mod outlier;
+mod practice;
+mod prediction;
+mod reset;
 mod run;
 mod segment;
+mod stats;
+mod sum_of_best;
+mod survival;
+mod time;
+mod time_save;
 mod timer;
 
+pub use attempt::Attempt;
+pub use bests::BestChange;
+pub use command::{CommandExecutor, TimerCommand};
+pub use comparison::Comparison;
+pub use delta::{Pace, SplitDelta};
+pub use edit::EditError;
+pub use event::TimerEvent;
+pub use format::{parse_time, ParseTimeError, TimeFormatter, TimeStyle};
+pub use game::{Category, Game, GameError};
+pub use group::{GroupError, SegmentGroup};
+pub use outlier::{Outlier, OutlierKind, OUTLIER_FENCE};
+pub use practice::Practice;
+pub use prediction::{Prediction, PREDICTION_SAMPLES};
+pub use reset::{ParseResetPolicyError, ResetDecision, ResetPolicy};
 pub use run::Run;
 pub use segment::Segment;
-pub use timer::{Split, Timer, TimerState};
+pub use stats::SegmentStats;
+pub use survival::SegmentSurvival;
+#[cfg(any(test, feature = "mock"))]
+pub use time::mock::MockTime;
+pub use time::{SignedDuration, StdTime, Time, TimeProvider, TimingMethod};
+pub use timer::{Split, SplitEditError, Timer, TimerSnapshot, TimerState};
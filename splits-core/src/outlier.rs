@@ -0,0 +1,176 @@
+use std::time::Duration;
+
+use crate::{attempt::Attempt, comparison::percentile, run::Run, time::TimingMethod};
+
+/// How many interquartile ranges a duration has to lie outside the middle half of
+/// a segment's durations to be an outlier.
+pub const OUTLIER_FENCE: f64 = 1.5;
+
+/// Segments with fewer recorded durations aren't checked for outliers.
+const MIN_HISTORY: usize = 4;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OutlierKind {
+    /// Far shorter than usual, like a split pressed twice.
+    Short,
+    /// Far longer than usual, like a break in the middle of an attempt.
+    Long,
+}
+
+/// A segment duration of an attempt that doesn't fit the segment's history.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Outlier {
+    /// The id of the attempt.
+    pub attempt: u32,
+    pub segment: usize,
+    pub time: Duration,
+    pub kind: OutlierKind,
+}
+
+impl Run {
+    /// Finds the segment durations that lie more than `fence` interquartile ranges
+    /// below the first or above the third quartile of their segment's durations.
+    pub fn outliers(&self, method: TimingMethod, fence: f64) -> Vec<Outlier> {
+        let mut outliers = Vec::new();
+        for segment in 0..self.len() {
+            let times: Vec<_> = self
+                .attempts
+                .iter()
+                .filter_map(|attempt| Some((attempt.id(), attempt.segment_time(segment)?[method])))
+                .collect();
+            if times.len() < MIN_HISTORY {
+                continue;
+            }
+            let mut sorted: Vec<_> = times.iter().map(|(_, time)| *time).collect();
+            sorted.sort();
+            let (Some(q1), Some(q3)) = (percentile(&sorted, 0.25), percentile(&sorted, 0.75))
+            else {
+                continue;
+            };
+            let range = (q3 - q1).mul_f64(fence);
+            for (attempt, time) in times {
+                let kind = match time {
+                    time if time < q1.saturating_sub(range) => OutlierKind::Short,
+                    time if time > q3 + range => OutlierKind::Long,
+                    _ => continue,
+                };
+                outliers.push(Outlier {
+                    attempt,
+                    segment,
+                    time,
+                    kind,
+                });
+            }
+        }
+        outliers
+    }
+
+    /// Excludes the durations of `outliers` from the history and rebuilds the bests
+    /// without them, see [`Run::recompute_bests`]. Splits after an excluded duration
+    /// no longer count towards best split times or the personal best.
+    pub fn exclude_outliers(&mut self, outliers: &[Outlier]) {
+        for outlier in outliers {
+            let attempt = self.attempts.iter_mut().find(|a| a.id() == outlier.attempt);
+            if let Some(attempt) = attempt {
+                attempt.exclude(outlier.segment);
+            }
+        }
+        self.recompute_bests();
+    }
+
+    /// Deletes the attempts with ids in `ids` and rebuilds the bests and the
+    /// personal best from the remaining ones. Returns the deleted attempts.
+    pub fn remove_attempts(&mut self, ids: &[u32]) -> Vec<Attempt> {
+        let (removed, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.attempts)
+            .into_iter()
+            .partition(|attempt| ids.contains(&attempt.id()));
+        self.attempts = kept;
+        let removed_personal_best = removed.iter().filter_map(Attempt::final_time).any(|time| {
+            TimingMethod::ALL
+                .into_iter()
+                .any(|method| Some(time[method]) == self.personal_best_time(method))
+        });
+        if removed_personal_best {
+            self.personal_best = Default::default();
+        }
+        self.recompute_bests();
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixture, Time};
+
+    fn run(splits: &[[u64; 2]]) -> Run {
+        let millis = |millis| Some(Time::from(Duration::from_millis(millis)));
+        fixture::run(&["a", "b"], splits.iter().map(|splits| splits.map(millis)))
+    }
+
+    #[test]
+    fn outliers() {
+        let mut run = run(&[
+            [10_000, 19_000],
+            [11_000, 21_000],
+            [10_500, 2_410_500],
+            [10_800, 21_800],
+            [11_200, 23_200],
+        ]);
+        let outliers = run.outliers(TimingMethod::RealTime, OUTLIER_FENCE);
+        assert_eq!(
+            outliers,
+            [Outlier {
+                attempt: 2,
+                segment: 1,
+                time: Duration::from_secs(2400),
+                kind: OutlierKind::Long,
+            }]
+        );
+        run.exclude_outliers(&outliers);
+        assert!(run.attempts()[2].is_excluded(1));
+        assert!(run
+            .outliers(TimingMethod::RealTime, OUTLIER_FENCE)
+            .is_empty());
+    }
+
+    #[test]
+    fn double_tap() {
+        let mut run = run(&[
+            [10_000, 20_000],
+            [10_100, 10_200],
+            [10_500, 20_500],
+            [10_800, 20_900],
+            [11_200, 21_000],
+        ]);
+        let outliers = run.outliers(TimingMethod::RealTime, OUTLIER_FENCE);
+        assert_eq!(outliers.len(), 1);
+        assert_eq!(outliers[0].kind, OutlierKind::Short);
+        assert_eq!(
+            run.personal_best_time(TimingMethod::RealTime),
+            Some(Duration::from_millis(10_200))
+        );
+
+        let mut excluded = run.clone();
+        excluded.exclude_outliers(&outliers);
+        assert_eq!(
+            excluded.segments()[1].best_time(),
+            Some(Time::from(Duration::from_millis(20_000)))
+        );
+        assert_eq!(
+            excluded.personal_best_time(TimingMethod::RealTime),
+            Some(Duration::from_millis(20_000))
+        );
+
+        run.remove_attempts(&[outliers[0].attempt]);
+        assert_eq!(run.attempts().len(), 4);
+        assert_eq!(
+            run.segments()[1].best_segment(),
+            Some(Time::from(Duration::from_millis(9_800)))
+        );
+        assert_eq!(
+            run.personal_best_time(TimingMethod::RealTime),
+            Some(Duration::from_millis(20_000))
+        );
+    }
+}
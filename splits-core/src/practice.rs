@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use crate::{
+    run::Run,
+    time::{StdTime, Time, TimeProvider, TimingMethod},
+    timer::{Timer, TimerState},
+};
+
+/// Times repeated attempts at a single segment. Every start and split pair is one
+/// repetition.
+pub struct Practice<T: TimeProvider = StdTime> {
+    segment: usize,
+    timer: Timer<T>,
+    session: Vec<Time>,
+}
+
+impl<T: TimeProvider> Practice<T> {
+    /// Practices the segment at index `segment`.
+    pub fn new(segment: usize) -> Self {
+        Self {
+            segment,
+            timer: Timer::new(1),
+            session: Vec::new(),
+        }
+    }
+
+    pub fn segment(&self) -> usize {
+        self.segment
+    }
+
+    /// The timer of the current repetition.
+    pub fn timer(&self) -> &Timer<T> {
+        &self.timer
+    }
+
+    pub fn timer_mut(&mut self) -> &mut Timer<T> {
+        &mut self.timer
+    }
+
+    /// Starts a new repetition, or ends the running one and returns its time.
+    pub fn start_or_split(&mut self) -> Option<Time> {
+        match self.timer.state() {
+            TimerState::NotStarted => self.timer.start(),
+            TimerState::Finished => {
+                self.timer.reset();
+                self.timer.start();
+            }
+            TimerState::Paused(_) => self.timer.unpause(),
+            TimerState::Running => {
+                self.timer.split();
+                if self.timer.state() == TimerState::Finished {
+                    let time = self.timer.current_time();
+                    self.session.push(time);
+                    return Some(time);
+                }
+            }
+        }
+        None
+    }
+
+    /// The times of every repetition finished in this session, oldest first.
+    pub fn session(&self) -> &[Time] {
+        &self.session
+    }
+
+    pub fn best(&self, method: TimingMethod) -> Option<Duration> {
+        self.session.iter().map(|time| time[method]).min()
+    }
+
+    /// The average of the last `window` repetitions.
+    pub fn rolling_average(&self, method: TimingMethod, window: usize) -> Option<Duration> {
+        let recent = &self.session[self.session.len().saturating_sub(window)..];
+        let total: Duration = recent.iter().map(|time| time[method]).sum();
+        (!recent.is_empty()).then(|| total / recent.len() as u32)
+    }
+}
+
+impl Run {
+    /// Records a practice repetition of the segment at `index`. It only counts
+    /// towards the segment's best with `update_best`.
+    pub fn add_practice_time(&mut self, index: usize, time: Time, update_best: bool) {
+        let segment = &mut self.segments[index];
+        segment.practice_history.push(time);
+        if update_best {
+            segment.add_segment_time(time);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MockTime, Segment};
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn session() {
+        let mut practice = Practice::<MockTime>::new(1);
+        for length in [4, 2, 3] {
+            assert_eq!(practice.start_or_split(), None);
+            MockTime::step(secs(length));
+            assert_eq!(practice.start_or_split(), Some(Time::from(secs(length))));
+        }
+        let method = TimingMethod::RealTime;
+        assert_eq!(practice.best(method), Some(secs(2)));
+        assert_eq!(
+            practice.rolling_average(method, 2),
+            Some(Duration::from_millis(2500))
+        );
+        assert_eq!(practice.rolling_average(method, 10), Some(secs(3)));
+    }
+
+    #[test]
+    fn practice_times_stay_out_of_bests() {
+        let mut run = Run::new("test".to_string(), vec![Segment::new("a")]);
+        run.add_practice_time(0, Time::from(secs(3)), false);
+        assert_eq!(run.segments()[0].best_segment(), None);
+        run.add_practice_time(0, Time::from(secs(2)), true);
+        assert_eq!(run.segments()[0].best_segment(), Some(Time::from(secs(2))));
+        assert_eq!(run.segments()[0].practice_history().len(), 2);
+    }
+}
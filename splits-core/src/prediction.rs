@@ -0,0 +1,182 @@
+use std::time::Duration;
+
+use crate::{
+    comparison::percentile,
+    run::Run,
+    time::{TimeProvider, TimingMethod},
+    timer::{Split, Timer, TimerState},
+};
+
+/// How many simulated finishes a prediction is made from.
+pub const PREDICTION_SAMPLES: usize = 2000;
+
+/// How often a sample of a live stretch of segments is redrawn before settling for
+/// the time it has already taken.
+const LIVE_TRIES: usize = 10;
+
+const SEED: u64 = 0x5eed;
+
+/// An estimate of how the current attempt will end, from simulated finishes.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Prediction {
+    /// The chance of finishing faster than the personal best, `None` without one.
+    pub pb_probability: Option<f64>,
+    /// The 10th percentile of the simulated final times.
+    pub low: Duration,
+    pub median: Duration,
+    /// The 90th percentile of the simulated final times.
+    pub high: Duration,
+}
+
+impl Run {
+    /// Predicts the final time of the timer's attempt by drawing every segment
+    /// left from the times it took in earlier attempts. The segment being run
+    /// only draws times longer than it has already taken. `None` if a segment
+    /// left has never been completed.
+    pub fn predict<T: TimeProvider>(
+        &self,
+        timer: &Timer<T>,
+        method: TimingMethod,
+        samples: usize,
+    ) -> Option<Prediction> {
+        let current_time = timer.current_time()[method];
+        let (base, first) = timer
+            .splits()
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(index, split)| match split {
+                Split::Split(time) => Some((time[method], index + 1)),
+                Split::Skipped => None,
+            })
+            .unwrap_or((Duration::ZERO, 0));
+        let current = match timer.state() {
+            TimerState::NotStarted | TimerState::Finished => first,
+            _ => timer.splits().len(),
+        };
+        let histories = (first..self.len())
+            .map(|index| {
+                let mut times: Vec<_> = self.segment_times(index, method).collect();
+                if times.is_empty() {
+                    times.extend(self.segments[index].best_segment().map(|time| time[method]));
+                }
+                (!times.is_empty()).then_some(times)
+            })
+            .collect::<Option<Vec<_>>>()?;
+        // Everything up to and including the current segment has been running since
+        // the last split, everything after it hasn't started yet.
+        let (live, rest) = histories.split_at((current + 1).min(self.len()) - first);
+        let elapsed = current_time.saturating_sub(base);
+        // A single live segment only draws from its times that are long enough,
+        // a stretch of skipped segments is redrawn instead.
+        let mut live = live.to_vec();
+        if let [times] = &mut live[..] {
+            times.retain(|&time| time >= elapsed);
+            if times.is_empty() {
+                times.push(elapsed);
+            }
+        }
+
+        let mut rng = Rng::new(SEED);
+        let draw = |rng: &mut Rng, histories: &[Vec<Duration>]| {
+            histories
+                .iter()
+                .map(|times| times[rng.below(times.len())])
+                .sum::<Duration>()
+        };
+        let mut finishes: Vec<_> = (0..samples.max(1))
+            .map(|_| {
+                let live = (0..LIVE_TRIES)
+                    .map(|_| draw(&mut rng, &live))
+                    .find(|&time| time >= elapsed)
+                    .unwrap_or(elapsed);
+                base + live + draw(&mut rng, rest)
+            })
+            .collect();
+        finishes.sort();
+
+        let pb_probability = self.personal_best_time(method).map(|pb| {
+            finishes.iter().filter(|&&time| time < pb).count() as f64 / finishes.len() as f64
+        });
+        Some(Prediction {
+            pb_probability,
+            low: percentile(&finishes, 0.1)?,
+            median: percentile(&finishes, 0.5)?,
+            high: percentile(&finishes, 0.9)?,
+        })
+    }
+}
+
+/// A small splitmix64 generator. Predictions are seeded with a constant, so they
+/// don't jump around between frames.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixture, MockTime};
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    fn run() -> Run {
+        fixture::run(
+            &["a", "b"],
+            [[10, 22], [12, 22]].map(|splits| splits.map(fixture::secs)),
+        )
+    }
+
+    #[test]
+    fn before_start() {
+        let run = run();
+        let timer = Timer::<MockTime>::new(run.len());
+        let prediction = run
+            .predict(&timer, TimingMethod::RealTime, PREDICTION_SAMPLES)
+            .unwrap();
+        assert!(prediction.low >= secs(20) && prediction.high <= secs(24));
+        // Only the finishes made of both fastest segments beat the personal best.
+        let probability = prediction.pb_probability.unwrap();
+        assert!((probability - 0.25).abs() < 0.05, "{probability}");
+    }
+
+    #[test]
+    fn live_segment() {
+        let run = run();
+        let mut timer = Timer::<MockTime>::new(run.len());
+        timer.start();
+        MockTime::step_secs(11.0);
+        let prediction = run
+            .predict(&timer, TimingMethod::RealTime, PREDICTION_SAMPLES)
+            .unwrap();
+        // The first segment can only take 12 seconds now.
+        assert_eq!(prediction.low, secs(22));
+        assert_eq!(prediction.pb_probability, Some(0.0));
+
+        MockTime::step_secs(10.0);
+        // Past the slowest first segment, only the second one is left to chance.
+        let prediction = run
+            .predict(&timer, TimingMethod::RealTime, PREDICTION_SAMPLES)
+            .unwrap();
+        assert!(prediction.low >= secs(31) && prediction.high <= secs(33));
+    }
+}
@@ -0,0 +1,176 @@
+use std::{fmt, str::FromStr};
+
+use crate::{run::Run, time::TimeProvider, timer::Timer};
+
+/// What happens to the current attempt when the timer is reset.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ResetPolicy {
+    #[default]
+    Always,
+    GoldsOnly,
+    /// Leaves the decision to the user, see [`ResetPolicy::decision`].
+    Ask,
+    Discard,
+}
+
+/// Which parts of an attempt are kept when it is reset.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ResetDecision {
+    /// Whether the attempt is added to the run's history.
+    pub record_attempt: bool,
+    /// Whether the attempt's segments can set new best segments.
+    pub keep_golds: bool,
+    /// Whether the attempt can set new best split times and a new personal best.
+    pub accept_personal_best: bool,
+    /// Whether only a finished attempt can set new best split times. Only a
+    /// finished attempt can set a new personal best either way.
+    pub personal_best_on_finish: bool,
+}
+
+#[derive(Debug, onlyerror::Error)]
+pub enum ParseResetPolicyError {
+    #[error("unknown reset policy `{0}`")]
+    Unknown(String),
+}
+
+impl ResetPolicy {
+    pub const ALL: [ResetPolicy; 4] = [
+        ResetPolicy::Always,
+        ResetPolicy::GoldsOnly,
+        ResetPolicy::Ask,
+        ResetPolicy::Discard,
+    ];
+
+    /// The decision the policy makes on its own, `None` if the user has to be asked.
+    pub fn decision(self) -> Option<ResetDecision> {
+        match self {
+            ResetPolicy::Ask => None,
+            policy => Some(ResetDecision {
+                record_attempt: policy == ResetPolicy::Always,
+                keep_golds: policy != ResetPolicy::Discard,
+                accept_personal_best: policy == ResetPolicy::Always,
+                personal_best_on_finish: true,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for ResetPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ResetPolicy::Always => "always",
+            ResetPolicy::GoldsOnly => "golds-only",
+            ResetPolicy::Ask => "ask",
+            ResetPolicy::Discard => "discard",
+        })
+    }
+}
+
+impl FromStr for ResetPolicy {
+    type Err = ParseResetPolicyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|policy| policy.to_string() == s)
+            .ok_or_else(|| ParseResetPolicyError::Unknown(s.to_string()))
+    }
+}
+
+impl Run {
+    /// Keeps what `decision` allows of the timer's current attempt, if it has been
+    /// started.
+    pub fn save_attempt<T: TimeProvider>(&mut self, timer: &Timer<T>, decision: ResetDecision) {
+        let Some(mut attempt) = timer.attempt(self.next_attempt_id()) else {
+            return;
+        };
+        attempt.resize(self.len());
+        let accept_personal_best = decision.accept_personal_best
+            && (attempt.is_finished() || !decision.personal_best_on_finish);
+        self.apply_attempt(&attempt, accept_personal_best, decision.keep_golds);
+        if decision.record_attempt {
+            self.attempts.push(attempt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{MockTime, Segment, Time, TimingMethod};
+
+    fn attempt(run: &mut Run, splits: &[f64], policy: ResetPolicy) {
+        let mut timer = Timer::<MockTime>::new(run.len());
+        timer.start();
+        let mut last = 0.0;
+        for split in splits {
+            MockTime::step_secs(split - last);
+            timer.split();
+            last = *split;
+        }
+        run.save_attempt(&timer, policy.decision().unwrap());
+    }
+
+    fn secs(secs: u64) -> Option<Time> {
+        Some(Time::from(Duration::from_secs(secs)))
+    }
+
+    #[test]
+    fn policies() {
+        let mut run = Run::new(
+            "test".to_string(),
+            vec![Segment::new("a"), Segment::new("b")],
+        );
+        attempt(&mut run, &[2.0], ResetPolicy::Always);
+        assert_eq!(run.attempts().len(), 1);
+        assert_eq!(run.segments()[0].best_segment(), secs(2));
+        assert_eq!(run.segments()[0].best_time(), None);
+
+        attempt(&mut run, &[1.0], ResetPolicy::GoldsOnly);
+        assert_eq!(run.attempts().len(), 1);
+        assert_eq!(run.segments()[0].best_segment(), secs(1));
+
+        attempt(&mut run, &[3.0, 4.0], ResetPolicy::Discard);
+        assert_eq!(run.attempts().len(), 1);
+        assert_eq!(run.segments()[1].best_segment(), None);
+        assert_eq!(run.personal_best_time(TimingMethod::RealTime), None);
+
+        attempt(&mut run, &[3.0, 4.0], ResetPolicy::Always);
+        assert_eq!(run.segments()[1].best_time(), secs(4));
+        assert_eq!(
+            run.personal_best_time(TimingMethod::RealTime),
+            Some(Duration::from_secs(4))
+        );
+    }
+
+    #[test]
+    fn personal_best_on_finish() {
+        let mut run = Run::new(
+            "test".to_string(),
+            vec![Segment::new("a"), Segment::new("b")],
+        );
+        let mut timer = Timer::<MockTime>::new(run.len());
+        timer.start();
+        MockTime::step_secs(2.0);
+        timer.split();
+        let mut decision = ResetPolicy::Always.decision().unwrap();
+        assert!(decision.personal_best_on_finish);
+        run.save_attempt(&timer, decision);
+        assert_eq!(run.segments()[0].best_time(), None);
+
+        decision.personal_best_on_finish = false;
+        run.save_attempt(&timer, decision);
+        assert_eq!(run.segments()[0].best_time(), secs(2));
+        assert_eq!(run.personal_best_time(TimingMethod::RealTime), None);
+    }
+
+    #[test]
+    fn parse() {
+        for policy in ResetPolicy::ALL {
+            assert_eq!(policy.to_string().parse::<ResetPolicy>().unwrap(), policy);
+        }
+        assert!("sometimes".parse::<ResetPolicy>().is_err());
+    }
+}
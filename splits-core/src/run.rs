@@ -1,50 +1,215 @@
 use std::time::Duration;
 
-use crate::{segment::Segment, timer::Split};
+use crate::{
+    attempt::Attempt,
+    group::SegmentGroup,
+    segment::Segment,
+    time::{SignedDuration, Time, TimeProvider, TimingMethod},
+    timer::Timer,
+};
 
+#[derive(Clone, Debug)]
 pub struct Run {
     title: String,
-    segments: Vec<Segment>,
+    pub(crate) segments: Vec<Segment>,
+    pub(crate) groups: Vec<SegmentGroup>,
+    start_offset: SignedDuration,
+    pub(crate) attempts: Vec<Attempt>,
+    pub(crate) personal_best: PersonalBest,
+}
+
+/// The split times of the fastest finished attempt. Every timing method has its own
+/// personal best, which need not come from the same attempt.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PersonalBest {
+    real_time: Vec<Option<Duration>>,
+    game_time: Vec<Option<Duration>>,
+}
+
+impl PersonalBest {
+    fn get(&self, method: TimingMethod) -> &Vec<Option<Duration>> {
+        match method {
+            TimingMethod::RealTime => &self.real_time,
+            TimingMethod::GameTime => &self.game_time,
+        }
+    }
+
+    fn get_mut(&mut self, method: TimingMethod) -> &mut Vec<Option<Duration>> {
+        match method {
+            TimingMethod::RealTime => &mut self.real_time,
+            TimingMethod::GameTime => &mut self.game_time,
+        }
+    }
+
+    /// Applies `edit` to the splits of every timing method that has a personal best.
+    pub(crate) fn edit<F: FnMut(&mut Vec<Option<Duration>>)>(&mut self, mut edit: F) {
+        for method in TimingMethod::ALL {
+            let splits = self.get_mut(method);
+            if !splits.is_empty() {
+                edit(splits);
+            }
+        }
+    }
 }
 
 impl Run {
     pub fn new(title: String, segments: Vec<Segment>) -> Self {
-        Self { title, segments }
+        Self::load(title, segments, Vec::new())
+    }
+
+    pub fn load(title: String, segments: Vec<Segment>, mut attempts: Vec<Attempt>) -> Self {
+        for attempt in &mut attempts {
+            attempt.resize(segments.len());
+        }
+        Self {
+            title,
+            segments,
+            groups: Vec::new(),
+            start_offset: SignedDuration::ZERO,
+            attempts,
+            personal_best: PersonalBest::default(),
+        }
+    }
+
+    pub fn start_offset(&self) -> SignedDuration {
+        self.start_offset
+    }
+
+    pub fn set_start_offset(&mut self, offset: SignedDuration) {
+        self.start_offset = offset;
     }
 
     pub fn add_segment(&mut self, segment: Segment) {
         self.segments.push(segment);
+        let len = self.segments.len();
+        self.attempts
+            .iter_mut()
+            .for_each(|attempt| attempt.resize(len));
+        for method in TimingMethod::ALL {
+            let splits = self.personal_best.get_mut(method);
+            if !splits.is_empty() {
+                splits.resize(len, None);
+            }
+        }
     }
 
-    pub fn update(&mut self, splits: &[Split]) {
-        self.segments
-            .iter_mut()
-            .zip(splits.iter())
-            .for_each(|(segment, split)| {
-                if let Split::Split(time) = split {
-                    segment.add_time(*time);
-                }
-            });
-        self.update_segments(splits);
-    }
-
-    fn update_segments(&mut self, splits: &[Split]) {
-        let mut last_time = Some(Duration::ZERO);
-
-        for (segment, split) in self.segments.iter_mut().zip(splits) {
-            if let Split::Split(time) = split {
-                if let Some(last) = last_time {
-                    if last < *time {
-                        segment.add_segment_time(*time - last);
-                    }
-                }
-                last_time = Some(*time);
-            } else {
-                last_time = None;
+    /// Records the timer's current attempt, if it has been started.
+    pub fn update<T: TimeProvider>(&mut self, timer: &Timer<T>) {
+        if let Some(attempt) = timer.attempt(self.next_attempt_id()) {
+            self.add_attempt(attempt);
+        }
+    }
+
+    /// Records `attempt`. Like saving a reset attempt, only a finished attempt can
+    /// set best split times.
+    pub fn add_attempt(&mut self, mut attempt: Attempt) {
+        attempt.resize(self.segments.len());
+        self.apply_attempt(&attempt, attempt.is_finished(), true);
+        self.attempts.push(attempt);
+    }
+
+    /// Updates the bests with the times of `attempt`, without recording it.
+    pub(crate) fn apply_attempt(&mut self, attempt: &Attempt, best_times: bool, golds: bool) {
+        for (index, segment) in self.segments.iter_mut().enumerate() {
+            if let Some(time) = attempt.split(index).filter(|_| best_times) {
+                segment.add_time(time);
+            }
+            if let Some(time) = attempt.segment_time(index).filter(|_| golds) {
+                segment.add_segment_time(time);
+            }
+        }
+        if best_times {
+            self.update_personal_best(attempt);
+        }
+    }
+
+    /// Rebuilds the personal best from the stored attempts, unless none of them is
+    /// finished.
+    pub(crate) fn recompute_personal_best(&mut self) {
+        if !self.attempts.iter().any(Attempt::is_finished) {
+            return;
+        }
+        self.personal_best = PersonalBest::default();
+        let attempts = std::mem::take(&mut self.attempts);
+        for attempt in &attempts {
+            self.update_personal_best(attempt);
+        }
+        self.attempts = attempts;
+    }
+
+    fn update_personal_best(&mut self, attempt: &Attempt) {
+        // Excluded durations put every split after them in doubt.
+        let final_time = attempt.final_time();
+        let Some(final_time) = final_time.filter(|_| attempt.excluded().is_empty()) else {
+            return;
+        };
+        for method in TimingMethod::ALL {
+            let beats_personal_best = self
+                .personal_best_time(method)
+                .is_none_or(|best| final_time[method] < best);
+            if beats_personal_best {
+                *self.personal_best.get_mut(method) = attempt
+                    .splits()
+                    .iter()
+                    .map(|split| split.map(|time| time[method]))
+                    .collect();
             }
         }
     }
 
+    /// The split times of the personal best, one per segment. Empty if no attempt
+    /// has been finished yet.
+    pub fn personal_best(&self, method: TimingMethod) -> &[Option<Duration>] {
+        self.personal_best.get(method)
+    }
+
+    pub fn personal_best_split(&self, index: usize, method: TimingMethod) -> Option<Duration> {
+        self.personal_best(method).get(index).copied().flatten()
+    }
+
+    pub fn personal_best_time(&self, method: TimingMethod) -> Option<Duration> {
+        self.personal_best(method).last().copied().flatten()
+    }
+
+    pub fn set_personal_best(&mut self, method: TimingMethod, mut splits: Vec<Option<Duration>>) {
+        if !splits.is_empty() {
+            splits.resize(self.segments.len(), None);
+        }
+        *self.personal_best.get_mut(method) = splits;
+    }
+
+    pub fn next_attempt_id(&self) -> u32 {
+        self.attempts
+            .iter()
+            .map(|attempt| attempt.id() + 1)
+            .max()
+            .unwrap_or(1)
+    }
+
+    pub fn attempts(&self) -> &[Attempt] {
+        &self.attempts
+    }
+
+    /// Every split time recorded for the segment at `index`, oldest first.
+    pub fn segment_history(&self, index: usize) -> impl Iterator<Item = Time> + '_ {
+        self.attempts
+            .iter()
+            .filter_map(move |attempt| attempt.split(index))
+    }
+
+    /// Every duration recorded for the segment at `index`, from attempts that split
+    /// both it and the segment before it.
+    pub fn segment_times(
+        &self,
+        index: usize,
+        method: TimingMethod,
+    ) -> impl Iterator<Item = Duration> + '_ {
+        self.attempts
+            .iter()
+            .filter_map(move |attempt| attempt.segment_time(index))
+            .map(move |time| time[method])
+    }
+
     pub fn segments(&self) -> &[Segment] {
         &self.segments
     }
@@ -53,7 +218,7 @@ impl Run {
         &self.title
     }
 
-    pub fn best_time(&self) -> Option<Duration> {
+    pub fn best_time(&self) -> Option<Time> {
         self.segments.last().and_then(|segment| segment.best_time)
     }
 
@@ -68,7 +233,7 @@ mod tests {
     use std::time::Duration;
 
     use crate::timer::Split;
-    use crate::{Run, Segment};
+    use crate::{Attempt, Run, Segment, Time, TimingMethod};
 
     fn run() -> Run {
         Run::new(
@@ -77,6 +242,22 @@ mod tests {
         )
     }
 
+    fn attempt(splits: &[Split]) -> Attempt {
+        Attempt::new(
+            0,
+            None,
+            None,
+            Duration::ZERO,
+            splits
+                .iter()
+                .map(|split| match split {
+                    Split::Skipped => None,
+                    Split::Split(time) => Some(*time),
+                })
+                .collect(),
+        )
+    }
+
     macro_rules! duration {
         ( $secs:literal s ) => {
             Duration::from_secs_f32($secs as f32)
@@ -87,6 +268,12 @@ mod tests {
         };
     }
 
+    macro_rules! time {
+        ( $($tt:tt)+ ) => {
+            Time::from(duration!($($tt)+))
+        };
+    }
+
     macro_rules! splits {
         (@array [$($prev:expr,)*]) => {
             &[$($prev,)*]
@@ -113,7 +300,7 @@ mod tests {
         };
 
         ($l:literal $s:ident) => {
-            Split::Split(duration!($l $s))
+            Split::Split(time!($l $s))
         };
 
         ( $($tt:tt)+ ) => {
@@ -127,22 +314,22 @@ mod tests {
         assert_eq!(run.segments[0].best_time, None);
         assert_eq!(run.segments[1].best_time, None);
         assert_eq!(run.segments[2].best_time, None);
-        run.update(splits![
+        run.add_attempt(attempt(splits![
             3 s,
             5 s,
             7 s
-        ]);
-        assert_eq!(run.segments[0].best_time, Some(duration!(3 s)));
-        assert_eq!(run.segments[1].best_time, Some(duration!(5 s)));
-        assert_eq!(run.segments[2].best_time, Some(duration!(7 s)));
-        run.update(splits![
+        ]));
+        assert_eq!(run.segments[0].best_time, Some(time!(3 s)));
+        assert_eq!(run.segments[1].best_time, Some(time!(5 s)));
+        assert_eq!(run.segments[2].best_time, Some(time!(7 s)));
+        run.add_attempt(attempt(splits![
             2 s,
             7 s,
             4 s
-        ]);
-        assert_eq!(run.segments[0].best_time, Some(duration!(2 s)));
-        assert_eq!(run.segments[1].best_time, Some(duration!(5 s)));
-        assert_eq!(run.segments[2].best_time, Some(duration!(4 s)));
+        ]));
+        assert_eq!(run.segments[0].best_time, Some(time!(2 s)));
+        assert_eq!(run.segments[1].best_time, Some(time!(5 s)));
+        assert_eq!(run.segments[2].best_time, Some(time!(4 s)));
     }
 
     #[test]
@@ -151,39 +338,143 @@ mod tests {
         assert_eq!(run.segments[0].best_segment, None);
         assert_eq!(run.segments[1].best_segment, None);
         assert_eq!(run.segments[2].best_segment, None);
-        run.update(splits![
+        run.add_attempt(attempt(splits![
             1 s,
             3 s,
             6 s,
-        ]);
-        assert_eq!(run.segments[0].best_segment, Some(duration!(1 s)));
-        assert_eq!(run.segments[1].best_segment, Some(duration!(2 s)));
-        assert_eq!(run.segments[2].best_segment, Some(duration!(3 s)));
-        run.update(splits![
+        ]));
+        assert_eq!(run.segments[0].best_segment, Some(time!(1 s)));
+        assert_eq!(run.segments[1].best_segment, Some(time!(2 s)));
+        assert_eq!(run.segments[2].best_segment, Some(time!(3 s)));
+        run.add_attempt(attempt(splits![
             3 s,
             4 s,
             10 s,
-        ]);
-        assert_eq!(run.segments[0].best_segment, Some(duration!(1 s)));
-        assert_eq!(run.segments[1].best_segment, Some(duration!(1 s)));
-        assert_eq!(run.segments[2].best_segment, Some(duration!(3 s)));
+        ]));
+        assert_eq!(run.segments[0].best_segment, Some(time!(1 s)));
+        assert_eq!(run.segments[1].best_segment, Some(time!(1 s)));
+        assert_eq!(run.segments[2].best_segment, Some(time!(3 s)));
     }
 
     #[test]
     fn skip_does_not_record_best_segment() {
         let mut run = run();
-        run.update(splits![
+        run.add_attempt(attempt(splits![
             2 s,
             5 s,
             8 s,
-        ]);
-        run.update(splits![
+        ]));
+        run.add_attempt(attempt(splits![
             1 s,
             skip,
             3 s,
-        ]);
-        assert_eq!(run.segments[0].best_segment, Some(duration!(1 s)));
-        assert_eq!(run.segments[1].best_segment, Some(duration!(3 s)));
-        assert_eq!(run.segments[2].best_segment, Some(duration!(3 s)));
+        ]));
+        assert_eq!(run.segments[0].best_segment, Some(time!(1 s)));
+        assert_eq!(run.segments[1].best_segment, Some(time!(3 s)));
+        assert_eq!(run.segments[2].best_segment, Some(time!(3 s)));
+    }
+
+    #[test]
+    fn game_time_history() {
+        let mut run = run();
+        run.add_attempt(attempt(&[
+            Split::Split(Time::new(duration!(3 s), duration!(2 s))),
+            Split::Split(Time::new(duration!(5 s), duration!(3 s))),
+            Split::Split(Time::new(duration!(7 s), duration!(6 s))),
+        ]));
+        assert_eq!(
+            run.segments[2].best_time,
+            Some(Time::new(duration!(7 s), duration!(6 s)))
+        );
+        assert_eq!(
+            run.segments[2].best_segment,
+            Some(Time::new(duration!(2 s), duration!(3 s)))
+        );
+    }
+
+    #[test]
+    fn attempts() {
+        let mut run = run();
+        run.add_attempt(attempt(splits![1 s, skip, 3 s]));
+        run.add_attempt(attempt(&[splits!(2 s)]));
+        assert_eq!(run.attempts().len(), 2);
+        assert_eq!(run.attempts()[1].splits(), &[Some(time!(2 s)), None, None]);
+        assert_eq!(
+            run.segment_history(0).collect::<Vec<_>>(),
+            vec![time!(1 s), time!(2 s)]
+        );
+        assert_eq!(run.segment_history(1).count(), 0);
+        assert_eq!(run.segment_history(2).collect::<Vec<_>>(), vec![time!(3 s)]);
+    }
+
+    #[test]
+    fn update_from_timer() {
+        use crate::{MockTime, Timer};
+
+        let mut run = run();
+        let mut timer = Timer::<MockTime>::new(run.len());
+        run.update(&timer);
+        assert!(run.attempts().is_empty());
+        timer.start();
+        MockTime::step_secs(1.0);
+        timer.split();
+        run.update(&timer);
+        timer.reset();
+        timer.start();
+        run.update(&timer);
+        assert_eq!(
+            run.attempts().iter().map(Attempt::id).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        // Neither attempt was finished.
+        assert_eq!(run.segments[0].best_time, None);
+    }
+
+    #[test]
+    fn personal_best() {
+        let mut run = run();
+        assert_eq!(run.personal_best_time(TimingMethod::RealTime), None);
+        run.add_attempt(attempt(splits![2 s, 5 s, 9 s]));
+        run.add_attempt(attempt(splits![1 s, 4 s]));
+        run.add_attempt(attempt(splits![3 s, 4 s, 10 s]));
+        assert_eq!(
+            run.personal_best(TimingMethod::RealTime),
+            &[
+                Some(duration!(2 s)),
+                Some(duration!(5 s)),
+                Some(duration!(9 s))
+            ]
+        );
+        run.add_attempt(attempt(splits![3 s, skip, 8 s]));
+        assert_eq!(
+            run.personal_best(TimingMethod::RealTime),
+            &[Some(duration!(3 s)), None, Some(duration!(8 s))]
+        );
+        // The 1 second split was never finished.
+        assert_eq!(run.segments[0].best_time, Some(time!(2 s)));
+    }
+
+    #[test]
+    fn personal_best_per_timing_method() {
+        let mut run = run();
+        let split = |real, game| Split::Split(Time::new(real, game));
+        run.add_attempt(attempt(&[
+            split(duration!(1 s), duration!(1 s)),
+            split(duration!(2 s), duration!(2 s)),
+            split(duration!(4 s), duration!(3 s)),
+        ]));
+        run.add_attempt(attempt(&[
+            split(duration!(1 s), duration!(1 s)),
+            split(duration!(2 s), duration!(2 s)),
+            split(duration!(5 s), duration!(2 s)),
+        ]));
+        assert_eq!(
+            run.personal_best_time(TimingMethod::RealTime),
+            Some(duration!(4 s))
+        );
+        assert_eq!(
+            run.personal_best_time(TimingMethod::GameTime),
+            Some(duration!(2 s))
+        );
     }
 }
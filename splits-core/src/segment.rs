@@ -1,78 +1,72 @@
-use std::time::Duration;
+use crate::time::Time;
 
 #[derive(Debug, Clone)]
 pub struct Segment {
     pub(crate) title: String,
-    pub(crate) history: Vec<Duration>,
-    pub(crate) best_time: Option<Duration>,
-    pub(crate) best_segment: Option<Duration>,
+    pub(crate) best_time: Option<Time>,
+    pub(crate) best_segment: Option<Time>,
+    pub(crate) practice_history: Vec<Time>,
+    pub(crate) legacy_history: Vec<Time>,
 }
 
 impl Segment {
     pub fn new(name: &str) -> Self {
         Self {
             title: name.into(),
-            history: Vec::new(),
             best_time: None,
             best_segment: None,
+            practice_history: Vec::new(),
+            legacy_history: Vec::new(),
         }
     }
 
-    pub fn load(
-        title: String,
-        history: Vec<Duration>,
-        best_time: Option<Duration>,
-        best_segment: Option<Duration>,
-    ) -> Self {
+    pub fn load(title: String, best_time: Option<Time>, best_segment: Option<Time>) -> Self {
         Self {
             title,
-            history,
             best_time,
             best_segment,
+            practice_history: Vec::new(),
+            legacy_history: Vec::new(),
         }
     }
 
-    pub fn history(&self) -> &[Duration] {
-        &self.history
-    }
-
     pub fn title(&self) -> &str {
         &self.title
     }
 
-    pub fn best_time(&self) -> Option<Duration> {
+    pub fn best_time(&self) -> Option<Time> {
         self.best_time
     }
 
-    pub fn best_segment(&self) -> Option<Duration> {
+    pub fn best_segment(&self) -> Option<Time> {
         self.best_segment
     }
 
-    pub fn add_time(&mut self, time: Duration) {
-        match self.best_time {
-            Some(best) => {
-                if time < best {
-                    self.best_time = Some(time);
-                }
-            }
-            None => {
-                self.best_time = Some(time);
-            }
-        }
-        self.history.push(time);
+    /// The times of every practice repetition of the segment, oldest first.
+    pub fn practice_history(&self) -> &[Time] {
+        &self.practice_history
     }
 
-    pub fn add_segment_time(&mut self, time: Duration) {
-        match self.best_segment {
-            Some(best) => {
-                if time < best {
-                    self.best_segment = Some(time);
-                }
-            }
-            None => {
-                self.best_segment = Some(time);
-            }
-        }
+    pub fn set_practice_history(&mut self, history: Vec<Time>) {
+        self.practice_history = history;
+    }
+
+    /// Split times recorded before whole attempts were, which can't be told apart
+    /// by attempt. They only count towards the best split time.
+    pub fn legacy_history(&self) -> &[Time] {
+        &self.legacy_history
+    }
+
+    pub fn set_legacy_history(&mut self, history: Vec<Time>) {
+        self.legacy_history = history;
+    }
+
+    pub fn add_time(&mut self, time: Time) {
+        self.best_time = Some(self.best_time.map_or(time, |best| best.min(time)));
+    }
+
+    pub fn add_segment_time(&mut self, time: Time) {
+        self.best_segment = Some(self.best_segment.map_or(time, |best| best.min(time)));
     }
 }
 
@@ -80,17 +74,32 @@ impl Segment {
 mod tests {
     use std::time::Duration;
 
-    use crate::Segment;
+    use crate::{Segment, Time};
+
+    fn time(secs: u64) -> Time {
+        Time::from(Duration::from_secs(secs))
+    }
 
     #[test]
     fn add_time() {
         let mut segment = Segment::new("test");
         assert_eq!(segment.best_time(), None);
-        segment.add_time(Duration::from_secs(3));
-        assert_eq!(segment.best_time(), Some(Duration::from_secs(3)));
-        segment.add_time(Duration::from_secs(5));
-        assert_eq!(segment.best_time(), Some(Duration::from_secs(3)));
-        segment.add_time(Duration::from_secs(2));
-        assert_eq!(segment.best_time(), Some(Duration::from_secs(2)));
+        segment.add_time(time(3));
+        assert_eq!(segment.best_time(), Some(time(3)));
+        segment.add_time(time(5));
+        assert_eq!(segment.best_time(), Some(time(3)));
+        segment.add_time(time(2));
+        assert_eq!(segment.best_time(), Some(time(2)));
+    }
+
+    #[test]
+    fn timing_methods_keep_separate_bests() {
+        let mut segment = Segment::new("test");
+        segment.add_time(Time::new(Duration::from_secs(3), Duration::from_secs(2)));
+        segment.add_time(Time::new(Duration::from_secs(4), Duration::from_secs(1)));
+        assert_eq!(
+            segment.best_time(),
+            Some(Time::new(Duration::from_secs(3), Duration::from_secs(1)))
+        );
     }
 }
@@ -0,0 +1,132 @@
+use std::time::Duration;
+
+use crate::{comparison::percentile, run::Run, time::TimingMethod};
+
+/// Statistics over every recorded duration of a segment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SegmentStats {
+    /// Never empty.
+    times: Vec<Duration>,
+}
+
+impl SegmentStats {
+    /// Statistics over `times`, `None` if there are none.
+    pub fn new(mut times: Vec<Duration>) -> Option<Self> {
+        if times.is_empty() {
+            return None;
+        }
+        times.sort();
+        Some(Self { times })
+    }
+
+    /// The durations the statistics are taken over, shortest first.
+    pub fn times(&self) -> &[Duration] {
+        &self.times
+    }
+
+    pub fn count(&self) -> usize {
+        self.times.len()
+    }
+
+    pub fn best(&self) -> Duration {
+        self.times[0]
+    }
+
+    pub fn worst(&self) -> Duration {
+        self.times[self.times.len() - 1]
+    }
+
+    pub fn mean(&self) -> Duration {
+        self.times.iter().sum::<Duration>() / self.times.len() as u32
+    }
+
+    pub fn median(&self) -> Duration {
+        self.percentile(0.5)
+    }
+
+    /// The `p`-th percentile for `p` between 0 and 1, interpolating between
+    /// recorded durations.
+    ///
+    /// # Panics
+    ///
+    /// If `p` isn't between 0 and 1, which includes NaN.
+    pub fn percentile(&self, p: f64) -> Duration {
+        assert!(
+            (0.0..=1.0).contains(&p),
+            "percentile {p} isn't between 0 and 1"
+        );
+        percentile(&self.times, p).expect("statistics are never empty")
+    }
+
+    /// The population standard deviation.
+    pub fn std_dev(&self) -> Duration {
+        let mean = self.mean().as_secs_f64();
+        let variance = self
+            .times
+            .iter()
+            .map(|time| (time.as_secs_f64() - mean).powi(2))
+            .sum::<f64>()
+            / self.times.len() as f64;
+        Duration::from_secs_f64(variance.sqrt())
+    }
+
+    /// How alike the durations are, from 0 to 1. It is one minus the standard
+    /// deviation relative to the mean, so 1 means every duration was the same.
+    pub fn consistency(&self) -> f64 {
+        let mean = self.mean().as_secs_f64();
+        if mean == 0.0 {
+            return 1.0;
+        }
+        (1.0 - self.std_dev().as_secs_f64() / mean).clamp(0.0, 1.0)
+    }
+}
+
+impl Run {
+    /// Statistics over the durations of the segment at `index`, taken from attempts
+    /// that split both it and the segment before it.
+    pub fn segment_stats(&self, index: usize, method: TimingMethod) -> Option<SegmentStats> {
+        SegmentStats::new(self.segment_times(index, method).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture;
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    #[should_panic]
+    fn nan_percentile() {
+        SegmentStats::new(vec![secs(1)])
+            .unwrap()
+            .percentile(f64::NAN);
+    }
+
+    #[test]
+    fn stats() {
+        let stats = SegmentStats::new([4, 2, 6, 4].map(secs).to_vec()).unwrap();
+        assert_eq!(stats.count(), 4);
+        assert_eq!(stats.best(), secs(2));
+        assert_eq!(stats.worst(), secs(6));
+        assert_eq!(stats.mean(), secs(4));
+        assert_eq!(stats.median(), secs(4));
+        assert_eq!(stats.percentile(1.0 / 3.0), secs(4));
+        assert_eq!(stats.std_dev().as_millis(), 1414);
+        assert!((stats.consistency() - (1.0 - 2f64.sqrt() / 4.0)).abs() < 1e-9);
+        assert_eq!(SegmentStats::new(Vec::new()), None);
+    }
+
+    #[test]
+    fn segment_durations() {
+        let run = fixture::run(
+            &["a", "b"],
+            [[1, 4], [2, 4]].map(|splits| splits.map(fixture::secs)),
+        );
+        let stats = run.segment_stats(1, TimingMethod::RealTime).unwrap();
+        assert_eq!(stats.times(), &[secs(2), secs(3)]);
+    }
+}
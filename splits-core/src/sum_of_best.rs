@@ -0,0 +1,209 @@
+use std::time::Duration;
+
+use crate::{
+    run::Run,
+    time::{TimeProvider, TimingMethod},
+    timer::{Split, Timer, TimerState},
+};
+
+/// The best recorded durations between split points, where point `0` is the start of
+/// the run and point `i + 1` is the split of segment `i`. Attempts that skipped
+/// segments contribute the combined duration of everything they skipped.
+struct BestRanges {
+    ranges: Vec<Vec<Option<Duration>>>,
+}
+
+impl BestRanges {
+    fn new(run: &Run, method: TimingMethod) -> Self {
+        let points = run.len() + 1;
+        let mut ranges = BestRanges {
+            ranges: vec![vec![None; points]; points],
+        };
+        for (index, segment) in run.segments().iter().enumerate() {
+            if let Some(best) = segment.best_segment() {
+                ranges.insert(index, index + 1, best[method]);
+            }
+        }
+        for attempt in run.attempts() {
+            let mut last = (0, Duration::ZERO);
+            for (index, split) in attempt.splits().iter().enumerate() {
+                if let Some(time) = split {
+                    let time = time[method];
+                    let range = time.checked_sub(last.1);
+                    if let Some(range) = range.filter(|_| !attempt.is_excluded(index)) {
+                        ranges.insert(last.0, index + 1, range);
+                    }
+                    last = (index + 1, time);
+                }
+            }
+        }
+        ranges
+    }
+
+    fn insert(&mut self, from: usize, to: usize, duration: Duration) {
+        let range = &mut self.ranges[from][to];
+        *range = Some(range.map_or(duration, |best| best.min(duration)));
+    }
+
+    fn get(&self, from: usize, to: usize) -> Option<Duration> {
+        self.ranges[from][to]
+    }
+
+    fn points(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// The shortest time from `from` to every later split point.
+    fn from(&self, from: usize) -> Vec<Option<Duration>> {
+        let mut from_start = vec![None; self.points()];
+        from_start[from] = Some(Duration::ZERO);
+        for to in from + 1..self.points() {
+            from_start[to] = (from..to)
+                .filter_map(|via| Some(from_start[via]? + self.get(via, to)?))
+                .min();
+        }
+        from_start
+    }
+
+    /// The shortest time from every split point to the end of the run.
+    fn to_end(&self) -> Vec<Option<Duration>> {
+        let end = self.points() - 1;
+        let mut to_end = vec![None; self.points()];
+        to_end[end] = Some(Duration::ZERO);
+        for from in (0..end).rev() {
+            to_end[from] = (from + 1..=end)
+                .filter_map(|to| Some(self.get(from, to)? + to_end[to]?))
+                .min();
+        }
+        to_end
+    }
+}
+
+impl Run {
+    /// The fastest time the run could be completed in, combining the best segments
+    /// of all attempts.
+    pub fn sum_of_best(&self, method: TimingMethod) -> Option<Duration> {
+        BestRanges::new(self, method).to_end()[0]
+    }
+
+    /// The fastest time every split has been reached in, combining the best
+    /// segments of all attempts.
+    pub fn best_splits(&self, method: TimingMethod) -> Vec<Option<Duration>> {
+        BestRanges::new(self, method).from(0).split_off(1)
+    }
+
+    /// The fastest the segments in `start..end` have been completed in, combining
+    /// best segments.
+    pub(crate) fn best_range(
+        &self,
+        start: usize,
+        end: usize,
+        method: TimingMethod,
+    ) -> Option<Duration> {
+        BestRanges::new(self, method).from(start)[end]
+    }
+
+    /// The fastest final time the live attempt can still reach, assuming every
+    /// remaining segment matches its best.
+    pub fn best_possible_time<T: TimeProvider>(
+        &self,
+        timer: &Timer<T>,
+        method: TimingMethod,
+    ) -> Option<Duration> {
+        match timer.state() {
+            TimerState::NotStarted => return self.sum_of_best(method),
+            TimerState::Finished => return Some(timer.current_time()[method]),
+            _ => {}
+        }
+        let ranges = BestRanges::new(self, method);
+        let to_end = ranges.to_end();
+        let current_time = timer.current_time()[method];
+        let (last_point, last_time) = timer
+            .splits()
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(index, split)| match split {
+                Split::Split(time) => Some((index + 1, time[method])),
+                Split::Skipped => None,
+            })
+            .unwrap_or((0, Duration::ZERO));
+        let from_last = ranges.from(last_point);
+        // The live segment ends at the first split point after the skipped ones, and
+        // can't end before the current time.
+        let next_point = timer.splits().len() + 1;
+        (next_point..ranges.points())
+            .filter_map(|to| {
+                let live = (last_point..next_point)
+                    .filter_map(|via| Some(from_last[via]? + ranges.get(via, to)?))
+                    .min()?;
+                Some((last_time + live).max(current_time) + to_end[to]?)
+            })
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::fixture::{self, secs};
+    use crate::{MockTime, Run, Time, Timer, TimingMethod};
+
+    fn run(attempts: &[&[Option<Time>]]) -> Run {
+        fixture::run(&["a", "b", "c"], attempts)
+    }
+
+    #[test]
+    fn sum_of_best() {
+        let run = run(&[&[secs(3), secs(5), secs(9)], &[secs(2), secs(6), secs(8)]]);
+        assert_eq!(
+            run.sum_of_best(TimingMethod::RealTime),
+            Some(Duration::from_secs(2 + 2 + 2))
+        );
+    }
+
+    #[test]
+    fn sum_of_best_without_data() {
+        let run = run(&[&[secs(3), None, None]]);
+        assert_eq!(run.sum_of_best(TimingMethod::RealTime), None);
+    }
+
+    #[test]
+    fn sum_of_best_across_skipped_segments() {
+        let run = run(&[&[secs(3), None, secs(7)], &[secs(4), secs(8), None]]);
+        // The last segment was only ever timed together with the one before it.
+        assert_eq!(
+            run.sum_of_best(TimingMethod::RealTime),
+            Some(Duration::from_secs(3 + 4))
+        );
+    }
+
+    #[test]
+    fn best_possible_time() {
+        let run = run(&[&[secs(3), secs(5), secs(9)]]);
+        let mut timer = Timer::<MockTime>::new(run.len());
+        assert_eq!(
+            run.best_possible_time(&timer, TimingMethod::RealTime),
+            Some(Duration::from_secs(9))
+        );
+        timer.start();
+        MockTime::step_secs(4.0);
+        timer.split();
+        assert_eq!(
+            run.best_possible_time(&timer, TimingMethod::RealTime),
+            Some(Duration::from_secs(4 + 2 + 4))
+        );
+        // Losing time on the live segment pushes the best possible time back.
+        MockTime::step_secs(3.0);
+        assert_eq!(
+            run.best_possible_time(&timer, TimingMethod::RealTime),
+            Some(Duration::from_secs(7 + 4))
+        );
+        timer.skip();
+        assert_eq!(
+            run.best_possible_time(&timer, TimingMethod::RealTime),
+            Some(Duration::from_secs(4 + 2 + 4))
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use crate::run::Run;
+
+/// How the recorded attempts fared in a single segment.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SegmentSurvival {
+    /// How many attempts made it to the segment.
+    pub reached: usize,
+    /// How many attempts were reset during the segment.
+    pub reset: usize,
+    /// The share of all attempts that got past the segment, from 0 to 1.
+    pub survival: f64,
+}
+
+impl SegmentSurvival {
+    /// The share of the attempts reaching the segment that were reset in it.
+    pub fn reset_rate(&self) -> f64 {
+        match self.reached {
+            0 => 0.0,
+            reached => self.reset as f64 / reached as f64,
+        }
+    }
+}
+
+impl Run {
+    /// Where the recorded attempts ended, one entry per segment. The survival of
+    /// the last segment is the share of attempts that were finished.
+    pub fn survival(&self) -> Vec<SegmentSurvival> {
+        let total = self.attempts.len();
+        let mut reached = total;
+        (0..self.len())
+            .map(|index| {
+                let reset = self
+                    .attempts
+                    .iter()
+                    .filter(|attempt| attempt.reset_segment() == Some(index))
+                    .count();
+                let segment = SegmentSurvival {
+                    reached,
+                    reset,
+                    survival: match total {
+                        0 => 0.0,
+                        total => (reached - reset) as f64 / total as f64,
+                    },
+                };
+                reached -= reset;
+                segment
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixture::{self, attempt, secs};
+
+    #[test]
+    fn survival() {
+        let mut run = fixture::run(
+            &["a", "b", "c"],
+            [
+                [None, None, None],
+                [secs(1), None, None],
+                [secs(1), secs(2), secs(3)],
+            ],
+        );
+        // A skipped segment is only told apart from a reset by the marker.
+        let mut skipped = attempt(3, &[None, secs(2), None]);
+        skipped.set_reset_segment(Some(2));
+        run.add_attempt(skipped);
+
+        let survival = run.survival();
+        let counts: Vec<_> = survival.iter().map(|s| (s.reached, s.reset)).collect();
+        assert_eq!(counts, [(4, 1), (3, 1), (2, 1)]);
+        assert_eq!(survival[2].survival, 0.25);
+        assert_eq!(survival[1].reset_rate(), 1.0 / 3.0);
+    }
+}
@@ -1,68 +1,344 @@
-use std::collections::HashMap;
-
-
-pub trait TimeProvider {
-    type Instant: Copy + Clone;
-    type Duration: Copy + Clone;
-
-    fn now() -> Self::Instant;
-    fn elapsed(since: Self::Instant) -> Self::Duration;
-}
-
-pub struct StdTime;
-
-impl TimeProvider for StdTime {
-    type Instant = std::time::Instant;
-    type Duration = std::time::Duration;
-
-    fn now() -> Self::Instant {
-        Self::Instant::now()
-    }
-
-    fn elapsed(since: Self::Instant) -> Self::Duration {
-        since.elapsed()
-    }
-}
-
-#[cfg(test)]
-pub mod mock {
-    use std::cell::RefCell;
-
-    use super::TimeProvider;
-
-    thread_local!(static TIME: RefCell<i32> = RefCell::new(0));
-
-    #[derive(Debug, Default)]
-    pub struct MockTime;
-
-    impl MockTime {
-        pub fn step(time: i32) {
-            TIME.with(|now| {
-                *now.borrow_mut() += time;
-            });
-        }
-    }
-
-    impl TimeProvider for MockTime {
-        type Instant = i32;
-        type Duration = i32;
-
-        fn now() -> Self::Instant {
-            TIME.with(|now| *now.borrow())
-        }
-
-        fn elapsed(instant: Self::Instant) -> Self::Duration {
-            TIME.with(|now| *now.borrow()) - instant
-        }
-    }
-
-    #[test]
-    fn mock_time() {
-        assert_eq!(MockTime::now(), 0);
-        MockTime::step(5);
-        assert_eq!(MockTime::now(), 5);
-        let t = MockTime::now();
-        MockTime::step(10);
-        assert_eq!(MockTime::elapsed(t), 10);
-    }
-}
\ No newline at end of file
+use std::{
+    fmt::{self, Debug},
+    ops::{Add, AddAssign, Index, IndexMut, Neg, Sub},
+    time::{Duration, SystemTime},
+};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TimingMethod {
+    #[default]
+    RealTime,
+    GameTime,
+}
+
+impl TimingMethod {
+    pub const ALL: [TimingMethod; 2] = [TimingMethod::RealTime, TimingMethod::GameTime];
+
+    pub fn toggle(self) -> Self {
+        match self {
+            TimingMethod::RealTime => TimingMethod::GameTime,
+            TimingMethod::GameTime => TimingMethod::RealTime,
+        }
+    }
+}
+
+impl fmt::Display for TimingMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimingMethod::RealTime => write!(f, "Real Time"),
+            TimingMethod::GameTime => write!(f, "Game Time"),
+        }
+    }
+}
+
+/// A point in a run, measured with every timing method.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Time {
+    pub real_time: Duration,
+    pub game_time: Duration,
+}
+
+impl Time {
+    pub const ZERO: Time = Time::new(Duration::ZERO, Duration::ZERO);
+
+    pub const fn new(real_time: Duration, game_time: Duration) -> Self {
+        Self {
+            real_time,
+            game_time,
+        }
+    }
+
+    pub fn get(&self, method: TimingMethod) -> Duration {
+        self[method]
+    }
+
+    /// Component-wise minimum, each timing method keeps its own best.
+    pub fn min(self, other: Time) -> Time {
+        Time::new(
+            self.real_time.min(other.real_time),
+            self.game_time.min(other.game_time),
+        )
+    }
+
+    /// Component-wise maximum, each timing method keeps its own worst.
+    pub fn max(self, other: Time) -> Time {
+        Time::new(
+            self.real_time.max(other.real_time),
+            self.game_time.max(other.game_time),
+        )
+    }
+
+    pub fn checked_sub(self, other: Time) -> Option<Time> {
+        Some(Time::new(
+            self.real_time.checked_sub(other.real_time)?,
+            self.game_time.checked_sub(other.game_time)?,
+        ))
+    }
+}
+
+impl From<Duration> for Time {
+    fn from(duration: Duration) -> Self {
+        Time::new(duration, duration)
+    }
+}
+
+impl Index<TimingMethod> for Time {
+    type Output = Duration;
+
+    fn index(&self, method: TimingMethod) -> &Duration {
+        match method {
+            TimingMethod::RealTime => &self.real_time,
+            TimingMethod::GameTime => &self.game_time,
+        }
+    }
+}
+
+impl IndexMut<TimingMethod> for Time {
+    fn index_mut(&mut self, method: TimingMethod) -> &mut Duration {
+        match method {
+            TimingMethod::RealTime => &mut self.real_time,
+            TimingMethod::GameTime => &mut self.game_time,
+        }
+    }
+}
+
+impl Add for Time {
+    type Output = Time;
+
+    fn add(self, rhs: Time) -> Time {
+        Time::new(
+            self.real_time + rhs.real_time,
+            self.game_time + rhs.game_time,
+        )
+    }
+}
+
+impl Sub for Time {
+    type Output = Time;
+
+    fn sub(self, rhs: Time) -> Time {
+        Time::new(
+            self.real_time - rhs.real_time,
+            self.game_time - rhs.game_time,
+        )
+    }
+}
+
+pub trait TimeProvider {
+    type Instant: Copy + Clone + Debug + PartialEq + Eq;
+
+    fn now() -> Self::Instant;
+    fn elapsed(since: Self::Instant) -> Duration;
+    fn duration_between(earlier: Self::Instant, later: Self::Instant) -> Duration;
+    /// The instant `by` before `instant`.
+    fn rewind(instant: Self::Instant, by: Duration) -> Self::Instant;
+    fn wall_clock() -> SystemTime;
+}
+
+#[derive(Debug, Default)]
+pub struct StdTime;
+
+impl TimeProvider for StdTime {
+    type Instant = std::time::Instant;
+
+    fn now() -> Self::Instant {
+        Self::Instant::now()
+    }
+
+    fn elapsed(since: Self::Instant) -> Duration {
+        since.elapsed()
+    }
+
+    fn duration_between(earlier: Self::Instant, later: Self::Instant) -> Duration {
+        later.duration_since(earlier)
+    }
+
+    fn rewind(instant: Self::Instant, by: Duration) -> Self::Instant {
+        instant.checked_sub(by).unwrap_or(instant)
+    }
+
+    fn wall_clock() -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// A duration that may be negative, such as a start offset or a difference between two times.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SignedDuration {
+    nanos: i128,
+}
+
+impl SignedDuration {
+    pub const ZERO: SignedDuration = SignedDuration { nanos: 0 };
+
+    pub fn negative(duration: Duration) -> Self {
+        -Self::from(duration)
+    }
+
+    /// `a - b`, which is negative when `b` is the longer duration.
+    pub fn difference(a: Duration, b: Duration) -> Self {
+        Self::from(a) - Self::from(b)
+    }
+
+    pub fn from_secs_f64(secs: f64) -> Self {
+        Self {
+            nanos: (secs * 1e9).round() as i128,
+        }
+    }
+
+    pub fn as_secs_f64(self) -> f64 {
+        self.nanos as f64 / 1e9
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.nanos < 0
+    }
+
+    pub fn abs(self) -> Duration {
+        duration_from_nanos(self.nanos.unsigned_abs())
+    }
+
+    /// The duration if it isn't negative.
+    pub fn to_duration(self) -> Option<Duration> {
+        (!self.is_negative()).then(|| self.abs())
+    }
+}
+
+fn duration_from_nanos(nanos: u128) -> Duration {
+    Duration::new(
+        (nanos / 1_000_000_000) as u64,
+        (nanos % 1_000_000_000) as u32,
+    )
+}
+
+impl From<Duration> for SignedDuration {
+    fn from(duration: Duration) -> Self {
+        Self {
+            nanos: duration.as_nanos() as i128,
+        }
+    }
+}
+
+impl Neg for SignedDuration {
+    type Output = SignedDuration;
+
+    fn neg(self) -> SignedDuration {
+        SignedDuration { nanos: -self.nanos }
+    }
+}
+
+impl Add for SignedDuration {
+    type Output = SignedDuration;
+
+    fn add(self, rhs: SignedDuration) -> SignedDuration {
+        SignedDuration {
+            nanos: self.nanos + rhs.nanos,
+        }
+    }
+}
+
+impl Add<Duration> for SignedDuration {
+    type Output = SignedDuration;
+
+    fn add(self, rhs: Duration) -> SignedDuration {
+        self + SignedDuration::from(rhs)
+    }
+}
+
+impl AddAssign<Duration> for SignedDuration {
+    fn add_assign(&mut self, rhs: Duration) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for SignedDuration {
+    type Output = SignedDuration;
+
+    fn sub(self, rhs: SignedDuration) -> SignedDuration {
+        self + -rhs
+    }
+}
+
+#[cfg(any(test, feature = "mock"))]
+pub mod mock {
+    use std::{
+        cell::Cell,
+        time::{Duration, SystemTime},
+    };
+
+    use super::TimeProvider;
+
+    thread_local!(static TIME: Cell<Duration> = const { Cell::new(Duration::ZERO) });
+
+    /// A manually stepped clock. Every thread has its own time, starting at zero
+    /// and at the unix epoch for the wall clock.
+    #[derive(Debug, Default)]
+    pub struct MockTime;
+
+    impl MockTime {
+        pub fn step(time: Duration) {
+            TIME.with(|now| now.set(now.get() + time));
+        }
+
+        pub fn step_secs(secs: f64) {
+            Self::step(Duration::from_secs_f64(secs));
+        }
+    }
+
+    impl TimeProvider for MockTime {
+        type Instant = Duration;
+
+        fn now() -> Self::Instant {
+            TIME.with(|now| now.get())
+        }
+
+        fn elapsed(since: Self::Instant) -> Duration {
+            Self::now() - since
+        }
+
+        fn duration_between(earlier: Self::Instant, later: Self::Instant) -> Duration {
+            later - earlier
+        }
+
+        fn rewind(instant: Self::Instant, by: Duration) -> Self::Instant {
+            instant.saturating_sub(by)
+        }
+
+        fn wall_clock() -> SystemTime {
+            SystemTime::UNIX_EPOCH + Self::now()
+        }
+    }
+
+    #[test]
+    fn mock_time() {
+        assert_eq!(MockTime::now(), Duration::ZERO);
+        MockTime::step(Duration::from_secs(5));
+        assert_eq!(MockTime::now(), Duration::from_secs(5));
+        let t = MockTime::now();
+        MockTime::step(Duration::from_secs(10));
+        assert_eq!(MockTime::elapsed(t), Duration::from_secs(10));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::SignedDuration;
+
+    #[test]
+    fn signed_duration() {
+        let offset = SignedDuration::from_secs_f64(-1.35);
+        assert!(offset.is_negative());
+        assert_eq!(offset.abs(), Duration::from_millis(1350));
+        assert_eq!(offset.to_duration(), None);
+        assert_eq!(
+            (offset + Duration::from_secs(2)).to_duration(),
+            Some(Duration::from_millis(650))
+        );
+        assert_eq!(
+            SignedDuration::difference(Duration::from_secs(1), Duration::from_secs(3)),
+            SignedDuration::negative(Duration::from_secs(2))
+        );
+    }
+}
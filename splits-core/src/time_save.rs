@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use crate::{run::Run, time::TimingMethod};
+
+impl Run {
+    /// How much faster the segment at `index` could be than in `comparison`, if it
+    /// matched its best segment.
+    pub fn possible_time_save(
+        &self,
+        index: usize,
+        comparison: &[Option<Duration>],
+        method: TimingMethod,
+    ) -> Option<Duration> {
+        let previous = match index {
+            0 => Duration::ZERO,
+            _ => (*comparison.get(index - 1)?)?,
+        };
+        let comparison_segment = (*comparison.get(index)?)?.checked_sub(previous)?;
+        let best_segment = self.segments().get(index)?.best_segment()?[method];
+        Some(comparison_segment.saturating_sub(best_segment))
+    }
+
+    /// The possible time save of every segment from `index` to the end of the run.
+    pub fn total_possible_time_save(
+        &self,
+        index: usize,
+        comparison: &[Option<Duration>],
+        method: TimingMethod,
+    ) -> Duration {
+        (index..self.len())
+            .filter_map(|index| self.possible_time_save(index, comparison, method))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{fixture, Run, TimingMethod};
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    fn run() -> Run {
+        fixture::run(
+            &["a", "b", "c"],
+            [[3, 7, 9], [4, 6, 10]].map(|splits| splits.map(fixture::secs)),
+        )
+    }
+
+    #[test]
+    fn possible_time_save() {
+        let run = run();
+        let method = TimingMethod::RealTime;
+        let comparison = [Some(secs(4)), Some(secs(6)), Some(secs(10))];
+        assert_eq!(
+            run.possible_time_save(0, &comparison, method),
+            Some(secs(1))
+        );
+        assert_eq!(
+            run.possible_time_save(1, &comparison, method),
+            Some(secs(0))
+        );
+        assert_eq!(
+            run.possible_time_save(2, &comparison, method),
+            Some(secs(2))
+        );
+        assert_eq!(
+            run.total_possible_time_save(0, &comparison, method),
+            secs(3)
+        );
+        assert_eq!(
+            run.total_possible_time_save(2, &comparison, method),
+            secs(2)
+        );
+    }
+
+    #[test]
+    fn missing_comparison() {
+        let run = run();
+        let comparison = [Some(secs(4)), None, Some(secs(10))];
+        assert_eq!(
+            run.possible_time_save(2, &comparison, TimingMethod::RealTime),
+            None
+        );
+    }
+}
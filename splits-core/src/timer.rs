@@ -1,23 +1,33 @@
-use std::time::{Duration, Instant};
+use std::{
+    fmt,
+    sync::mpsc::Receiver,
+    time::{Duration, SystemTime},
+};
 
 use itertools::Itertools;
 
+use crate::{
+    attempt::Attempt,
+    event::{Subscribers, TimerEvent},
+    time::{SignedDuration, StdTime, Time, TimeProvider, TimingMethod},
+};
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub enum TimerState {
+pub enum TimerState<I = std::time::Instant> {
     NotStarted,
     Running,
-    Paused(Instant),
+    Paused(I),
     Finished,
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Split {
     Skipped,
-    Split(Duration),
+    Split(Time),
 }
 
 impl Split {
-    pub fn unwrap_time(self) -> Duration {
+    pub fn unwrap_time(self) -> Time {
         match self {
             Split::Skipped => panic!("unwrap_time on skipped split!"),
             Split::Split(time) => time,
@@ -25,33 +35,124 @@ impl Split {
     }
 }
 
-pub struct Timer {
-    start_time: Instant,
+#[derive(Debug, PartialEq, Eq)]
+pub enum SplitEditError {
+    /// The split at the index hasn't been reached yet.
+    NotReached(usize),
+    /// The final split of a finished attempt can't be cleared.
+    ClearFinal,
+    /// Split times have to increase from one split to the next.
+    NotIncreasing,
+}
+
+impl std::error::Error for SplitEditError {}
+
+/// Splits are numbered from 1, as they are shown.
+impl fmt::Display for SplitEditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotReached(index) => write!(f, "split {} hasn't been reached yet", index + 1),
+            Self::ClearFinal => {
+                f.write_str("the final split of a finished attempt can't be cleared")
+            }
+            Self::NotIncreasing => {
+                f.write_str("split times have to increase from one split to the next")
+            }
+        }
+    }
+}
+
+/// The state of a started attempt in wall-clock terms, so it can outlive the process.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimerSnapshot {
+    pub started_at: SystemTime,
+    pub paused_at: Option<SystemTime>,
+    pub paused_time: Duration,
+    pub offset: SignedDuration,
+    pub game_time: SignedDuration,
+    pub game_time_since: Option<SystemTime>,
+    pub game_time_paused: bool,
+    pub splits: Vec<Split>,
+    pub finished: bool,
+}
+
+pub struct Timer<T: TimeProvider = StdTime> {
+    start_time: T::Instant,
+    started_at: SystemTime,
+    finished_at: Option<SystemTime>,
     paused_time: Duration,
+    offset: SignedDuration,
+    game_time: SignedDuration,
+    game_time_since: Option<T::Instant>,
+    game_time_paused: bool,
     num_splits: usize,
     splits: Vec<Split>,
-    state: TimerState,
+    undone: Vec<Split>,
+    state: TimerState<T::Instant>,
+    subscribers: Subscribers,
 }
 
-impl Timer {
+impl<T: TimeProvider> Timer<T> {
     pub fn new(num_splits: usize) -> Self {
         Self {
             num_splits,
-            start_time: Instant::now(),
+            start_time: T::now(),
+            started_at: T::wall_clock(),
+            finished_at: None,
             paused_time: Duration::ZERO,
+            offset: SignedDuration::ZERO,
+            game_time: SignedDuration::ZERO,
+            game_time_since: None,
+            game_time_paused: false,
             splits: Vec::with_capacity(num_splits),
+            undone: Vec::new(),
             state: TimerState::NotStarted,
+            subscribers: Subscribers::default(),
         }
     }
 
-    pub fn state(&self) -> TimerState {
+    /// Calls `callback` with every event the timer emits from now on.
+    pub fn subscribe<F: FnMut(&TimerEvent) + Send + 'static>(&mut self, callback: F) {
+        self.subscribers.subscribe(callback);
+    }
+
+    /// Returns a receiver for every event the timer emits from now on. Dropping
+    /// the receiver unsubscribes it.
+    pub fn channel(&mut self) -> Receiver<TimerEvent> {
+        self.subscribers.channel()
+    }
+
+    fn emit(&mut self, event: TimerEvent) {
+        self.subscribers.emit(event);
+    }
+
+    /// The time shown when the timer starts. A negative offset counts down to zero
+    /// before the first segment begins.
+    pub fn set_offset(&mut self, offset: SignedDuration) {
+        if self.state == TimerState::NotStarted {
+            self.offset = offset;
+        }
+    }
+
+    pub fn offset(&self) -> SignedDuration {
+        self.offset
+    }
+
+    pub fn state(&self) -> TimerState<T::Instant> {
         self.state
     }
 
     pub fn start(&mut self) {
         if self.state == TimerState::NotStarted {
-            self.start_time = Instant::now();
+            self.start_time = T::now();
+            self.started_at = T::wall_clock();
+            self.finished_at = None;
+            self.paused_time = Duration::ZERO;
+            self.game_time = self.offset;
+            self.game_time_since = Some(self.start_time);
+            self.game_time_paused = false;
             self.state = TimerState::Running;
+            self.emit(TimerEvent::Started);
         }
     }
 
@@ -66,14 +167,20 @@ impl Timer {
 
     pub fn pause(&mut self) {
         if self.state == TimerState::Running {
-            self.state = TimerState::Paused(Instant::now());
+            self.state = TimerState::Paused(T::now());
+            self.stop_game_clock();
+            self.emit(TimerEvent::Paused);
         }
     }
 
     pub fn unpause(&mut self) {
         if let TimerState::Paused(paused_at) = self.state {
-            self.paused_time += paused_at.elapsed();
+            self.paused_time += T::elapsed(paused_at);
             self.state = TimerState::Running;
+            if !self.game_time_paused {
+                self.game_time_since = Some(T::now());
+            }
+            self.emit(TimerEvent::Resumed);
         }
     }
 
@@ -85,19 +192,84 @@ impl Timer {
         }
     }
 
+    pub fn pause_game_time(&mut self) {
+        if let TimerState::Running | TimerState::Paused(_) = self.state {
+            if !self.game_time_paused {
+                self.game_time_paused = true;
+                self.stop_game_clock();
+                self.emit(TimerEvent::GameTimePaused);
+            }
+        }
+    }
+
+    pub fn resume_game_time(&mut self) {
+        if self.game_time_paused {
+            self.game_time_paused = false;
+            if self.state == TimerState::Running {
+                self.game_time_since = Some(T::now());
+            }
+            self.emit(TimerEvent::GameTimeResumed);
+        }
+    }
+
+    pub fn toggle_game_time_pause(&mut self) {
+        if self.game_time_paused {
+            self.resume_game_time();
+        } else {
+            self.pause_game_time();
+        }
+    }
+
+    pub fn is_game_time_paused(&self) -> bool {
+        self.game_time_paused
+    }
+
+    /// Overwrites the game time, e.g. with the in-game timer read by an external tool.
+    pub fn set_game_time(&mut self, time: Duration) {
+        if let TimerState::Running | TimerState::Paused(_) = self.state {
+            self.game_time = time.into();
+            if self.game_time_since.is_some() {
+                self.game_time_since = Some(T::now());
+            }
+            self.emit(TimerEvent::GameTimeSet { time });
+        }
+    }
+
+    fn stop_game_clock(&mut self) {
+        if let Some(since) = self.game_time_since.take() {
+            self.game_time += T::elapsed(since);
+        }
+    }
+
     pub fn split(&mut self) {
-        if self.state == TimerState::Running {
-            self.splits.push(Split::Split(self.current_time()));
+        if self.state == TimerState::Running && self.countdown().is_none() {
+            let time = self.current_time();
+            self.undone.clear();
+            self.splits.push(Split::Split(time));
+            self.emit(TimerEvent::Split {
+                index: self.splits.len() - 1,
+                time,
+            });
             if self.splits.len() == self.num_splits {
-                self.state = TimerState::Finished;
+                self.finish();
             }
         }
     }
 
+    fn finish(&mut self) {
+        self.state = TimerState::Finished;
+        self.finished_at = Some(T::wall_clock());
+        self.emit(TimerEvent::Finished);
+    }
+
     pub fn skip(&mut self) {
         if let TimerState::Running | TimerState::Paused(_) = self.state {
             if self.splits.len() != self.num_splits - 1 {
+                self.undone.clear();
                 self.splits.push(Split::Skipped);
+                self.emit(TimerEvent::Skipped {
+                    index: self.splits.len() - 1,
+                });
             }
         }
     }
@@ -106,25 +278,198 @@ impl Timer {
         if self.state != TimerState::NotStarted && !self.splits.is_empty() {
             if self.state == TimerState::Finished {
                 self.state = TimerState::Running;
+                self.finished_at = None;
             }
-            self.splits.pop();
+            self.undone.extend(self.splits.pop());
+            self.emit(TimerEvent::Undone {
+                index: self.splits.len(),
+            });
         }
     }
 
-    pub fn current_time(&self) -> Duration {
-        match self.state {
-            TimerState::NotStarted => Duration::ZERO,
-            TimerState::Running => self.start_time.elapsed() - self.paused_time,
-            TimerState::Paused(paused_at) => {
-                paused_at.duration_since(self.start_time) - self.paused_time
+    /// Restores the split most recently removed by [`Timer::undo`].
+    pub fn redo(&mut self) {
+        if let TimerState::Running | TimerState::Paused(_) = self.state {
+            if let Some(split) = self.undone.pop() {
+                self.splits.push(split);
+                self.emit(TimerEvent::Redone {
+                    index: self.splits.len() - 1,
+                });
+                if self.splits.len() == self.num_splits {
+                    self.finish();
+                }
+            }
+        }
+    }
+
+    /// Changes the time of a split already recorded in the attempt, or skips it with
+    /// `None`. Every time has to lie between the splits around it, and before the
+    /// current time while the attempt is still going.
+    pub fn set_split(&mut self, index: usize, time: Option<Time>) -> Result<(), SplitEditError> {
+        if index >= self.splits.len() {
+            return Err(SplitEditError::NotReached(index));
+        }
+        let Some(time) = time else {
+            if self.state == TimerState::Finished && index == self.splits.len() - 1 {
+                return Err(SplitEditError::ClearFinal);
             }
+            self.splits[index] = Split::Skipped;
+            self.undone.clear();
+            self.emit(TimerEvent::SplitEdited { index });
+            return Ok(());
+        };
+        let recorded = |split: &Split| match split {
+            Split::Split(time) => Some(*time),
+            Split::Skipped => None,
+        };
+        let previous = self.splits[..index].iter().rev().find_map(recorded);
+        let next = match self.splits[index + 1..].iter().find_map(recorded) {
+            None if self.state != TimerState::Finished => Some(self.current_time()),
+            next => next,
+        };
+        let increasing = TimingMethod::ALL.into_iter().all(|method| {
+            previous.is_none_or(|previous| previous[method] <= time[method])
+                && next.is_none_or(|next| time[method] <= next[method])
+        });
+        if !increasing {
+            return Err(SplitEditError::NotIncreasing);
+        }
+        self.splits[index] = Split::Split(time);
+        self.undone.clear();
+        self.emit(TimerEvent::SplitEdited { index });
+        Ok(())
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    pub fn current_time(&self) -> Time {
+        match self.state {
+            TimerState::NotStarted => Time::ZERO,
             TimerState::Finished => self.splits.last().unwrap().unwrap_time(),
+            _ => Time::new(self.current_real_time(), self.current_game_time()),
+        }
+    }
+
+    /// The time left until the timer reaches zero, while a negative offset is
+    /// counting down.
+    pub fn countdown(&self) -> Option<Duration> {
+        match self.state {
+            TimerState::Running | TimerState::Paused(_) => {
+                let time = self.signed_real_time();
+                time.is_negative().then(|| time.abs())
+            }
+            _ => None,
         }
     }
 
+    fn signed_real_time(&self) -> SignedDuration {
+        let elapsed = match self.state {
+            TimerState::Paused(paused_at) => T::duration_between(self.start_time, paused_at),
+            _ => T::elapsed(self.start_time),
+        };
+        self.offset + (elapsed - self.paused_time)
+    }
+
+    fn current_real_time(&self) -> Duration {
+        self.signed_real_time().to_duration().unwrap_or_default()
+    }
+
+    fn current_game_time(&self) -> Duration {
+        (self.game_time + self.game_time_since.map_or(Duration::ZERO, T::elapsed))
+            .to_duration()
+            .unwrap_or_default()
+    }
+
     pub fn reset(&mut self) {
+        if self.state != TimerState::NotStarted {
+            self.emit(TimerEvent::Reset);
+        }
         self.state = TimerState::NotStarted;
+        self.game_time_since = None;
+        self.game_time_paused = false;
         self.splits.clear();
+        self.undone.clear();
+    }
+
+    pub fn snapshot(&self) -> Option<TimerSnapshot> {
+        let wall_clock = |instant| self.started_at + T::duration_between(self.start_time, instant);
+        let (paused_at, finished) = match self.state {
+            TimerState::NotStarted => return None,
+            TimerState::Running => (None, false),
+            TimerState::Paused(paused_at) => (Some(wall_clock(paused_at)), false),
+            TimerState::Finished => (None, true),
+        };
+        Some(TimerSnapshot {
+            started_at: self.started_at,
+            paused_at,
+            paused_time: self.paused_time,
+            offset: self.offset,
+            game_time: self.game_time,
+            game_time_since: self.game_time_since.map(wall_clock),
+            game_time_paused: self.game_time_paused,
+            splits: self.splits.clone(),
+            finished,
+        })
+    }
+
+    /// Continues the attempt in `snapshot`. Time that passed on the wall clock since
+    /// the snapshot was taken counts towards the attempt.
+    pub fn restore(&mut self, snapshot: TimerSnapshot) {
+        let now = T::now();
+        let wall_now = T::wall_clock();
+        let instant = |wall_clock: SystemTime| {
+            T::rewind(now, wall_now.duration_since(wall_clock).unwrap_or_default())
+        };
+        self.start_time = instant(snapshot.started_at);
+        self.started_at = snapshot.started_at;
+        self.finished_at = None;
+        self.paused_time = snapshot.paused_time;
+        self.offset = snapshot.offset;
+        self.game_time = snapshot.game_time;
+        self.game_time_since = snapshot.game_time_since.map(instant);
+        self.game_time_paused = snapshot.game_time_paused;
+        self.splits = snapshot.splits;
+        self.splits.truncate(self.num_splits);
+        self.undone.clear();
+        self.state = if snapshot.finished && self.splits.len() == self.num_splits {
+            TimerState::Finished
+        } else if let Some(paused_at) = snapshot.paused_at {
+            TimerState::Paused(instant(paused_at))
+        } else {
+            TimerState::Running
+        };
+    }
+
+    /// The attempt so far, with every segment that wasn't split left empty.
+    pub fn attempt(&self, id: u32) -> Option<Attempt> {
+        let paused_time = match self.state {
+            TimerState::NotStarted => return None,
+            TimerState::Paused(paused_at) => self.paused_time + T::elapsed(paused_at),
+            _ => self.paused_time,
+        };
+        let mut splits: Vec<_> = self
+            .splits
+            .iter()
+            .map(|split| match split {
+                Split::Skipped => None,
+                Split::Split(time) => Some(*time),
+            })
+            .collect();
+        splits.resize(self.num_splits, None);
+        let mut attempt = Attempt::new(
+            id,
+            Some(self.started_at),
+            Some(self.finished_at.unwrap_or_else(T::wall_clock)),
+            paused_time,
+            splits,
+        );
+        attempt.set_reset_segment(
+            self.current_index()
+                .filter(|&index| index < self.num_splits),
+        );
+        Some(attempt)
     }
 
     pub fn current_index(&self) -> Option<usize> {
@@ -135,12 +480,13 @@ impl Timer {
         }
     }
 
-    pub fn get_segment_times(&self) -> Vec<Option<Duration>> {
+    pub fn get_segment_times(&self) -> Vec<Option<Time>> {
         self.splits()
             .iter()
             .tuple_windows()
             .map(|tuple| match tuple {
-                (Split::Split(first), Split::Split(second)) => Some(*second - *first),
+                // Setting the game time can move it backwards between two splits.
+                (Split::Split(first), Split::Split(second)) => second.checked_sub(*first),
                 _ => None,
             })
             .collect()
@@ -153,11 +499,25 @@ impl Timer {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Timer, TimerState};
+    use std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use crate::{
+        MockTime, SignedDuration, Split, SplitEditError, Time, Timer, TimerEvent, TimerState,
+    };
+
+    fn secs(secs: f64) -> Duration {
+        Duration::from_secs_f64(secs)
+    }
 
     #[test]
     fn splits() {
-        let mut timer = Timer::new(3);
+        let mut timer = Timer::<MockTime>::new(3);
         assert_eq!(timer.state(), TimerState::NotStarted);
         timer.start();
         assert_eq!(timer.state(), TimerState::Running);
@@ -170,11 +530,304 @@ mod tests {
     }
 
     #[test]
-    fn test() {
-        let mut timer = Timer::new(5);
+    fn current_time() {
+        let mut timer = Timer::<MockTime>::new(2);
+        MockTime::step_secs(4.0);
+        assert_eq!(timer.current_time(), Time::ZERO);
+        timer.start();
+        MockTime::step_secs(1.5);
+        assert_eq!(timer.current_time(), Time::from(secs(1.5)));
+        timer.split();
+        MockTime::step_secs(2.0);
+        assert_eq!(timer.current_time(), Time::from(secs(3.5)));
+        timer.split();
+        MockTime::step_secs(2.0);
+        assert_eq!(timer.current_time(), Time::from(secs(3.5)));
+        assert_eq!(timer.get_segment_times(), vec![Some(Time::from(secs(2.0)))]);
+    }
+
+    #[test]
+    fn pause() {
+        let mut timer = Timer::<MockTime>::new(2);
+        timer.start();
+        MockTime::step_secs(1.0);
+        timer.pause();
+        MockTime::step_secs(10.0);
+        assert_eq!(timer.current_time(), Time::from(secs(1.0)));
+        timer.unpause();
+        MockTime::step_secs(1.0);
+        assert_eq!(timer.current_time(), Time::from(secs(2.0)));
+        timer.split();
+        assert!(matches!(timer.splits(), [Split::Split(time)] if *time == Time::from(secs(2.0))));
+    }
+
+    #[test]
+    fn game_time() {
+        let mut timer = Timer::<MockTime>::new(2);
+        timer.start();
+        MockTime::step_secs(1.0);
+        timer.pause_game_time();
+        MockTime::step_secs(3.0);
+        assert_eq!(timer.current_time(), Time::new(secs(4.0), secs(1.0)));
+        timer.pause();
+        MockTime::step_secs(1.0);
+        timer.unpause();
+        assert_eq!(timer.current_time(), Time::new(secs(4.0), secs(1.0)));
+        timer.resume_game_time();
+        MockTime::step_secs(1.0);
+        timer.split();
+        assert_eq!(
+            timer.splits()[0].unwrap_time(),
+            Time::new(secs(5.0), secs(2.0))
+        );
+        timer.set_game_time(secs(10.0));
+        MockTime::step_secs(1.0);
+        assert_eq!(timer.current_time(), Time::new(secs(6.0), secs(11.0)));
+
+        let events = timer.channel();
+        timer.set_game_time(secs(1.0));
+        assert_eq!(
+            events.try_recv(),
+            Ok(TimerEvent::GameTimeSet { time: secs(1.0) })
+        );
+        timer.split();
+        // The game time went backwards, so the segment has no duration.
+        assert_eq!(timer.get_segment_times(), [None]);
+    }
+
+    #[test]
+    fn undo_finished() {
+        let mut timer = Timer::<MockTime>::new(1);
+        timer.start();
+        MockTime::step_secs(1.0);
+        timer.split();
+        assert_eq!(timer.state(), TimerState::Finished);
+        MockTime::step_secs(1.0);
+        timer.undo();
+        assert_eq!(timer.state(), TimerState::Running);
+        assert_eq!(timer.current_time(), Time::from(secs(2.0)));
+    }
+
+    #[test]
+    fn events() {
+        let mut timer = Timer::<MockTime>::new(2);
+        let events = timer.channel();
+        timer.start();
+        MockTime::step_secs(1.0);
+        timer.split();
+        timer.pause();
+        timer.pause();
+        timer.unpause();
+        timer.undo();
+        timer.skip();
+        timer.reset();
+        assert_eq!(
+            events.try_iter().collect::<Vec<_>>(),
+            vec![
+                TimerEvent::Started,
+                TimerEvent::Split {
+                    index: 0,
+                    time: Time::from(secs(1.0))
+                },
+                TimerEvent::Paused,
+                TimerEvent::Resumed,
+                TimerEvent::Undone { index: 0 },
+                TimerEvent::Skipped { index: 0 },
+                TimerEvent::Reset,
+            ]
+        );
+    }
+
+    #[test]
+    fn finished_event() {
+        let mut timer = Timer::<MockTime>::new(1);
+        let finished = Arc::new(AtomicBool::new(false));
+        let flag = finished.clone();
+        timer.subscribe(move |event| {
+            if *event == TimerEvent::Finished {
+                flag.store(true, Ordering::SeqCst);
+            }
+        });
+        timer.start();
+        timer.split();
+        assert!(finished.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn redo() {
+        let mut timer = Timer::<MockTime>::new(3);
+        timer.start();
+        MockTime::step_secs(1.0);
+        timer.split();
+        timer.skip();
+        MockTime::step_secs(1.0);
+        timer.split();
+        assert_eq!(timer.state(), TimerState::Finished);
+        timer.undo();
+        timer.undo();
+        timer.undo();
+        assert!(timer.splits().is_empty());
+        timer.redo();
+        timer.redo();
+        assert!(matches!(timer.splits(), [Split::Split(_), Split::Skipped]));
+        timer.redo();
+        assert_eq!(timer.state(), TimerState::Finished);
+        assert_eq!(timer.current_time(), Time::from(secs(2.0)));
+        assert!(!timer.can_redo());
+    }
+
+    #[test]
+    fn split_clears_redo() {
+        let mut timer = Timer::<MockTime>::new(3);
+        timer.start();
+        timer.split();
+        timer.undo();
+        assert!(timer.can_redo());
+        timer.split();
+        assert!(!timer.can_redo());
+        timer.undo();
+        timer.skip();
+        assert!(!timer.can_redo());
+    }
+
+    #[test]
+    fn negative_offset() {
+        let mut timer = Timer::<MockTime>::new(2);
+        timer.set_offset(SignedDuration::from_secs_f64(-1.5));
+        timer.start();
+        MockTime::step_secs(1.0);
+        assert_eq!(timer.countdown(), Some(secs(0.5)));
+        assert_eq!(timer.current_time(), Time::ZERO);
+        timer.split();
+        assert!(timer.splits().is_empty());
+        MockTime::step_secs(1.0);
+        assert_eq!(timer.countdown(), None);
+        assert_eq!(timer.current_time(), Time::from(secs(0.5)));
+    }
+
+    #[test]
+    fn positive_offset() {
+        let mut timer = Timer::<MockTime>::new(2);
+        timer.set_offset(SignedDuration::from_secs_f64(2.0));
+        timer.start();
+        MockTime::step_secs(1.0);
+        assert_eq!(timer.countdown(), None);
+        assert_eq!(timer.current_time(), Time::from(secs(3.0)));
+    }
+
+    #[test]
+    fn snapshot_and_restore() {
+        let mut timer = Timer::<MockTime>::new(3);
+        assert_eq!(timer.snapshot(), None);
+        timer.start();
+        MockTime::step_secs(1.0);
+        timer.split();
+        timer.pause_game_time();
+        MockTime::step_secs(1.0);
+        timer.pause();
+        let snapshot = timer.snapshot().unwrap();
+
+        MockTime::step_secs(5.0);
+        let mut restored = Timer::<MockTime>::new(3);
+        restored.restore(snapshot);
+        assert!(matches!(restored.state(), TimerState::Paused(_)));
+        assert_eq!(restored.current_time(), Time::new(secs(2.0), secs(1.0)));
+        assert_eq!(restored.splits().len(), 1);
+        restored.unpause();
+        restored.resume_game_time();
+        MockTime::step_secs(1.0);
+        assert_eq!(restored.current_time(), Time::new(secs(3.0), secs(2.0)));
+    }
+
+    #[test]
+    fn restore_keeps_running() {
+        let mut timer = Timer::<MockTime>::new(3);
+        timer.start();
+        MockTime::step_secs(1.0);
+        let snapshot = timer.snapshot().unwrap();
+        MockTime::step_secs(2.0);
+        let mut restored = Timer::<MockTime>::new(3);
+        restored.restore(snapshot);
+        assert_eq!(restored.state(), TimerState::Running);
+        assert_eq!(restored.current_time(), Time::from(secs(3.0)));
+    }
+
+    #[test]
+    fn attempt() {
+        let mut timer = Timer::<MockTime>::new(3);
+        assert_eq!(timer.attempt(1), None);
+        MockTime::step_secs(100.0);
+        timer.start();
+        MockTime::step_secs(1.0);
+        timer.split();
+        timer.skip();
+        timer.pause();
+        MockTime::step_secs(2.0);
+        let attempt = timer.attempt(1).unwrap();
+        assert_eq!(attempt.id(), 1);
+        assert_eq!(attempt.splits(), &[Some(Time::from(secs(1.0))), None, None]);
+        assert_eq!(attempt.reset_segment(), Some(2));
+        assert_eq!(attempt.paused_time(), secs(2.0));
+        assert_eq!(
+            attempt.started(),
+            Some(std::time::SystemTime::UNIX_EPOCH + secs(100.0))
+        );
+        assert!(!attempt.is_finished());
+    }
+
+    #[test]
+    fn set_split() {
+        let mut timer = Timer::<MockTime>::new(3);
+        timer.start();
+        for _ in 0..2 {
+            MockTime::step_secs(2.0);
+            timer.split();
+        }
+        MockTime::step_secs(2.0);
+        let time = |secs| Some(Time::from(self::secs(secs)));
+
+        assert_eq!(timer.set_split(0, time(1.5)), Ok(()));
+        assert_eq!(timer.splits()[0], Split::Split(time(1.5).unwrap()));
+        assert_eq!(
+            timer.set_split(0, time(4.5)),
+            Err(SplitEditError::NotIncreasing)
+        );
+        assert_eq!(
+            timer.set_split(1, time(6.5)),
+            Err(SplitEditError::NotIncreasing)
+        );
+        assert_eq!(
+            timer.set_split(2, time(5.0)),
+            Err(SplitEditError::NotReached(2))
+        );
+        assert_eq!(
+            SplitEditError::NotReached(2).to_string(),
+            "split 3 hasn't been reached yet"
+        );
+        assert_eq!(timer.set_split(0, None), Ok(()));
+        assert_eq!(timer.set_split(1, time(1.0)), Ok(()));
+
+        timer.split();
+        assert_eq!(timer.set_split(2, None), Err(SplitEditError::ClearFinal));
+        assert_eq!(timer.set_split(2, time(7.0)), Ok(()));
+        assert_eq!(timer.current_time(), time(7.0).unwrap());
+    }
+
+    #[test]
+    fn set_split_clears_redo() {
+        let mut timer = Timer::<MockTime>::new(3);
         timer.start();
-        for _ in 0..100 {
-            println!("{:?}", timer.current_time());
+        for _ in 0..2 {
+            MockTime::step_secs(2.0);
+            timer.split();
         }
+        timer.undo();
+        assert!(timer.can_redo());
+        MockTime::step_secs(2.0);
+        // Redoing would bring back the 4 second split after the edited one.
+        assert_eq!(timer.set_split(0, Some(Time::from(secs(5.0)))), Ok(()));
+        assert!(!timer.can_redo());
+        timer.redo();
+        assert_eq!(timer.splits().len(), 1);
     }
 }
@@ -1,32 +1,326 @@
-use std::time::Duration;
+use std::{
+    collections::BTreeMap,
+    fmt,
+    time::{Duration, SystemTime},
+};
 
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{self, IntoDeserializer},
+    Deserialize, Deserializer, Serialize,
+};
 use splits_core as core;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RunSchema {
     title: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    best_time: Option<f64>,
-    segments: Vec<SegmentSchema>,
+    best_time: Option<TimeSchema>,
+    #[serde(
+        default,
+        skip_serializing_if = "is_zero",
+        deserialize_with = "deserialize_signed_seconds"
+    )]
+    start_offset: f64,
+    segments: Vec<SegmentEntrySchema>,
+    #[serde(default, skip_serializing_if = "PersonalBestSchema::is_empty")]
+    personal_best: PersonalBestSchema,
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "deserialize_attempts"
+    )]
+    attempts: Vec<AttemptSchema>,
 }
 
 impl From<&core::Run> for RunSchema {
     fn from(run: &core::Run) -> Self {
         Self {
             title: run.title().to_string(),
-            best_time: run.best_time().map(|d| d.as_secs_f64()),
-            segments: run.segments().iter().map(SegmentSchema::from).collect(),
+            best_time: run.best_time().map(TimeSchema::from),
+            start_offset: run.start_offset().as_secs_f64(),
+            segments: SegmentEntrySchema::from_run(run),
+            personal_best: PersonalBestSchema::from(run),
+            attempts: run.attempts().iter().map(AttemptSchema::from).collect(),
         }
     }
 }
 
 impl From<RunSchema> for core::Run {
     fn from(run: RunSchema) -> Self {
-        core::Run::new(
-            run.title,
-            run.segments.into_iter().map(From::from).collect(),
-        )
+        let mut segments = Vec::new();
+        let mut groups = Vec::new();
+        for entry in run.segments {
+            match entry {
+                SegmentEntrySchema::Group {
+                    group,
+                    segments: children,
+                } => {
+                    let start = segments.len();
+                    segments.extend(children.into_iter().map(core::Segment::from));
+                    groups.push(core::SegmentGroup::new(&group, start..segments.len()));
+                }
+                SegmentEntrySchema::Segment(segment) => segments.push(segment.into()),
+            }
+        }
+        let attempts = run.attempts.into_iter().map(From::from).collect();
+        let mut core_run = core::Run::load(run.title, segments, attempts);
+        for group in groups {
+            // Groups in the schema can't overlap, so only empty ones are dropped here.
+            let _ = core_run.add_group(group);
+        }
+        core_run.set_start_offset(core::SignedDuration::from_secs_f64(run.start_offset));
+        core_run.set_personal_best(
+            core::TimingMethod::RealTime,
+            from_optional_secs(run.personal_best.real_time),
+        );
+        core_run.set_personal_best(
+            core::TimingMethod::GameTime,
+            from_optional_secs(run.personal_best.game_time),
+        );
+        core_run
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GameSchema {
+    title: String,
+    categories: Vec<CategorySchema>,
+}
+
+impl From<&core::Game> for GameSchema {
+    fn from(game: &core::Game) -> Self {
+        Self {
+            title: game.title().to_string(),
+            categories: game.categories().iter().map(CategorySchema::from).collect(),
+        }
+    }
+}
+
+impl TryFrom<GameSchema> for core::Game {
+    type Error = core::GameError;
+
+    fn try_from(game: GameSchema) -> Result<Self, Self::Error> {
+        let mut core_game = core::Game::new(game.title);
+        for category in game.categories {
+            core_game.add_category(category.into())?;
+        }
+        Ok(core_game)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CategorySchema {
+    #[serde(flatten)]
+    run: RunSchema,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    variables: BTreeMap<String, String>,
+}
+
+impl From<&core::Category> for CategorySchema {
+    fn from(category: &core::Category) -> Self {
+        Self {
+            run: RunSchema::from(category.run()),
+            variables: category.variables().clone(),
+        }
+    }
+}
+
+impl From<CategorySchema> for core::Category {
+    fn from(category: CategorySchema) -> Self {
+        Self::load(category.run.into(), category.variables)
+    }
+}
+
+fn is_zero(value: &f64) -> bool {
+    *value == 0.0
+}
+
+/// Reads seconds written either as a number or as a time like `-0:01.35`.
+fn deserialize_signed_seconds<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Seconds {
+        Number(f64),
+        Text(String),
+    }
+
+    let seconds = match Seconds::deserialize(deserializer)? {
+        Seconds::Number(seconds) => seconds,
+        Seconds::Text(text) => core::parse_time(&text)
+            .map(core::SignedDuration::as_secs_f64)
+            .map_err(de::Error::custom)?,
+    };
+    // Rules out what `Duration::from_secs_f64` would panic on later.
+    Duration::try_from_secs_f64(seconds.abs()).map_err(de::Error::custom)?;
+    Ok(seconds)
+}
+
+/// Like [`deserialize_signed_seconds`], for durations that can't be negative.
+fn deserialize_seconds<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    let seconds = deserialize_signed_seconds(deserializer)?;
+    if seconds < 0.0 {
+        return Err(de::Error::custom(format!(
+            "`{seconds}` is negative, which durations can't be"
+        )));
+    }
+    Ok(seconds)
+}
+
+/// Seconds that are read with [`deserialize_seconds`], to check them inside of
+/// lists and options.
+#[derive(Deserialize)]
+struct CheckedSeconds(#[serde(deserialize_with = "deserialize_seconds")] f64);
+
+fn deserialize_optional_seconds<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Option<f64>>, D::Error> {
+    let seconds = Vec::<Option<CheckedSeconds>>::deserialize(deserializer)?;
+    Ok(seconds
+        .into_iter()
+        .map(|seconds| seconds.map(|CheckedSeconds(seconds)| seconds))
+        .collect())
+}
+
+/// Reads a point in time as seconds since the Unix epoch.
+fn deserialize_unix_secs<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    let secs = deserialize_seconds(deserializer)?;
+    // Rules out what adding to `SystemTime::UNIX_EPOCH` would panic on later.
+    SystemTime::UNIX_EPOCH
+        .checked_add(Duration::from_secs_f64(secs))
+        .ok_or_else(|| de::Error::custom(format!("`{secs}` is too far in the future")))?;
+    Ok(secs)
+}
+
+fn deserialize_optional_unix_secs<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<f64>, D::Error> {
+    #[derive(Deserialize)]
+    struct UnixSecs(#[serde(deserialize_with = "deserialize_unix_secs")] f64);
+
+    Ok(Option::<UnixSecs>::deserialize(deserializer)?.map(|UnixSecs(secs)| secs))
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PersonalBestSchema {
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "deserialize_optional_seconds"
+    )]
+    real_time: Vec<Option<f64>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "deserialize_optional_seconds"
+    )]
+    game_time: Vec<Option<f64>>,
+}
+
+impl PersonalBestSchema {
+    fn is_empty(&self) -> bool {
+        self.real_time.is_empty() && self.game_time.is_empty()
+    }
+}
+
+impl From<&core::Run> for PersonalBestSchema {
+    fn from(run: &core::Run) -> Self {
+        Self {
+            real_time: to_optional_secs(run.personal_best(core::TimingMethod::RealTime)),
+            game_time: to_optional_secs(run.personal_best(core::TimingMethod::GameTime)),
+        }
+    }
+}
+
+fn to_optional_secs(times: &[Option<Duration>]) -> Vec<Option<f64>> {
+    times
+        .iter()
+        .map(|time| time.map(|d| d.as_secs_f64()))
+        .collect()
+}
+
+fn from_optional_secs(times: Vec<Option<f64>>) -> Vec<Option<Duration>> {
+    times
+        .into_iter()
+        .map(|time| time.map(Duration::from_secs_f64))
+        .collect()
+}
+
+/// An entry of a run's segment list, either a single segment or a named group of them.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum SegmentEntrySchema {
+    Group {
+        group: String,
+        segments: Vec<SegmentSchema>,
+    },
+    Segment(SegmentSchema),
+}
+
+/// Tells groups and segments apart by their keys, so errors in either aren't lost
+/// like they would be trying one after the other.
+impl<'de> Deserialize<'de> for SegmentEntrySchema {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Entry {
+            group: Option<String>,
+            segments: Option<Vec<SegmentSchema>>,
+            title: Option<String>,
+            #[serde(default)]
+            best_time: Option<TimeSchema>,
+            #[serde(default)]
+            best_segment: Option<TimeSchema>,
+            #[serde(default)]
+            practice: Vec<TimeSchema>,
+            #[serde(default)]
+            history: Vec<TimeSchema>,
+        }
+
+        let entry = Entry::deserialize(deserializer)?;
+        match (entry.group, entry.segments, entry.title) {
+            (Some(group), Some(segments), None) => Ok(Self::Group { group, segments }),
+            (Some(_), None, _) => Err(de::Error::missing_field("segments")),
+            (None, None, Some(title)) => Ok(Self::Segment(SegmentSchema {
+                title,
+                best_time: entry.best_time,
+                best_segment: entry.best_segment,
+                practice: entry.practice,
+                history: entry.history,
+            })),
+            (None, _, None) => Err(de::Error::missing_field("title")),
+            _ => Err(de::Error::custom(
+                "a segment entry has either a `title` or a `group` with `segments`",
+            )),
+        }
+    }
+}
+
+impl SegmentEntrySchema {
+    fn from_run(run: &core::Run) -> Vec<Self> {
+        let mut entries = Vec::new();
+        let mut index = 0;
+        while index < run.len() {
+            match run
+                .groups()
+                .iter()
+                .find(|group| group.range().start == index)
+            {
+                Some(group) => {
+                    entries.push(Self::Group {
+                        group: group.title().to_string(),
+                        segments: run.segments()[group.range()]
+                            .iter()
+                            .map(SegmentSchema::from)
+                            .collect(),
+                    });
+                    index = group.range().end;
+                }
+                None => {
+                    entries.push(Self::Segment(SegmentSchema::from(&run.segments()[index])));
+                    index += 1;
+                }
+            }
+        }
+        entries
     }
 }
 
@@ -34,36 +328,628 @@ impl From<RunSchema> for core::Run {
 pub struct SegmentSchema {
     title: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    best_time: Option<f64>,
+    best_time: Option<TimeSchema>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    best_segment: Option<f64>,
+    best_segment: Option<TimeSchema>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    history: Vec<f64>,
+    practice: Vec<TimeSchema>,
+    /// The split times of the segment, as written before whole attempts were
+    /// recorded. Kept apart from the attempts.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    history: Vec<TimeSchema>,
 }
 
 impl From<&core::Segment> for SegmentSchema {
     fn from(segment: &core::Segment) -> Self {
         Self {
             title: segment.title().to_string(),
-            best_time: segment.best_time().map(|d| d.as_secs_f64()),
-            best_segment: segment.best_segment().map(|d| d.as_secs_f64()),
-            history: segment.history().iter().map(|d| d.as_secs_f64()).collect(),
+            best_time: segment.best_time().map(TimeSchema::from),
+            best_segment: segment.best_segment().map(TimeSchema::from),
+            practice: segment
+                .practice_history()
+                .iter()
+                .copied()
+                .map(TimeSchema::from)
+                .collect(),
+            history: segment
+                .legacy_history()
+                .iter()
+                .copied()
+                .map(TimeSchema::from)
+                .collect(),
         }
     }
 }
 
 impl From<SegmentSchema> for core::Segment {
     fn from(segment: SegmentSchema) -> Self {
-        Self::load(
+        let mut core_segment = Self::load(
             segment.title,
-            segment
-                .history
+            segment.best_time.map(From::from),
+            segment.best_segment.map(From::from),
+        );
+        core_segment.set_practice_history(segment.practice.into_iter().map(From::from).collect());
+        core_segment.set_legacy_history(segment.history.into_iter().map(From::from).collect());
+        core_segment
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttemptSchema {
+    id: u32,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_optional_unix_secs"
+    )]
+    started: Option<f64>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_optional_unix_secs"
+    )]
+    ended: Option<f64>,
+    #[serde(
+        default,
+        skip_serializing_if = "is_zero",
+        deserialize_with = "deserialize_seconds"
+    )]
+    paused_time: f64,
+    splits: Vec<Option<TimeSchema>>,
+    /// Left out by older files, where it is inferred from the splits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reset_segment: Option<usize>,
+    /// Segments whose durations are left out of the history, such as outliers.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    excluded: Vec<usize>,
+}
+
+/// Rejects attempts whose reset or excluded segments lie past their splits.
+fn deserialize_attempts<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<AttemptSchema>, D::Error> {
+    let attempts = Vec::<AttemptSchema>::deserialize(deserializer)?;
+    for attempt in &attempts {
+        let len = attempt.splits.len();
+        let indices = attempt.reset_segment.iter().chain(&attempt.excluded);
+        if let Some(index) = indices.copied().find(|&index| index >= len) {
+            return Err(de::Error::custom(format!(
+                "attempt {} refers to segment {index}, but only has {len} splits",
+                attempt.id
+            )));
+        }
+    }
+    Ok(attempts)
+}
+
+impl From<&core::Attempt> for AttemptSchema {
+    fn from(attempt: &core::Attempt) -> Self {
+        Self {
+            id: attempt.id(),
+            started: attempt.started().map(to_unix_secs),
+            ended: attempt.ended().map(to_unix_secs),
+            paused_time: attempt.paused_time().as_secs_f64(),
+            splits: attempt
+                .splits()
                 .iter()
-                .copied()
-                .map(Duration::from_secs_f64)
+                .map(|split| split.map(TimeSchema::from))
+                .collect(),
+            reset_segment: attempt.reset_segment(),
+            excluded: attempt.excluded().to_vec(),
+        }
+    }
+}
+
+impl From<AttemptSchema> for core::Attempt {
+    fn from(attempt: AttemptSchema) -> Self {
+        let mut core_attempt = Self::new(
+            attempt.id,
+            attempt.started.map(from_unix_secs),
+            attempt.ended.map(from_unix_secs),
+            Duration::from_secs_f64(attempt.paused_time),
+            attempt
+                .splits
+                .into_iter()
+                .map(|split| split.map(From::from))
                 .collect(),
-            segment.best_time.map(Duration::from_secs_f64),
-            segment.best_segment.map(Duration::from_secs_f64),
-        )
+        );
+        if attempt.reset_segment.is_some() {
+            core_attempt.set_reset_segment(attempt.reset_segment);
+        }
+        for index in attempt.excluded {
+            core_attempt.exclude(index);
+        }
+        core_attempt
+    }
+}
+
+/// Files written before game time was tracked store a single number of seconds,
+/// which is read as both real time and game time. People editing files by hand can
+/// write it as a time like `1:02:05.4` as well.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum TimeSchema {
+    Methods { real_time: f64, game_time: f64 },
+    Seconds(f64),
+}
+
+/// Tells the forms apart by the kind of value, so errors in either aren't lost like
+/// they would be trying one after the other.
+impl<'de> Deserialize<'de> for TimeSchema {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TimeVisitor;
+
+        impl<'de> de::Visitor<'de> for TimeVisitor {
+            type Value = TimeSchema;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("seconds, a time or a map of `real_time` and `game_time`")
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                self.visit_f64(value as f64)
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+                self.visit_f64(value as f64)
+            }
+
+            fn visit_f64<E: de::Error>(self, value: f64) -> Result<Self::Value, E> {
+                deserialize_seconds(value.into_deserializer()).map(TimeSchema::Seconds)
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                deserialize_seconds(value.into_deserializer()).map(TimeSchema::Seconds)
+            }
+
+            fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                #[derive(Deserialize)]
+                struct Methods {
+                    #[serde(deserialize_with = "deserialize_seconds")]
+                    real_time: f64,
+                    #[serde(deserialize_with = "deserialize_seconds")]
+                    game_time: f64,
+                }
+
+                let methods = Methods::deserialize(de::value::MapAccessDeserializer::new(map))?;
+                Ok(TimeSchema::Methods {
+                    real_time: methods.real_time,
+                    game_time: methods.game_time,
+                })
+            }
+        }
+
+        deserializer.deserialize_any(TimeVisitor)
+    }
+}
+
+impl From<core::Time> for TimeSchema {
+    fn from(time: core::Time) -> Self {
+        Self::Methods {
+            real_time: time.real_time.as_secs_f64(),
+            game_time: time.game_time.as_secs_f64(),
+        }
+    }
+}
+
+impl From<TimeSchema> for core::Time {
+    fn from(time: TimeSchema) -> Self {
+        match time {
+            TimeSchema::Methods {
+                real_time,
+                game_time,
+            } => core::Time::new(
+                Duration::from_secs_f64(real_time),
+                Duration::from_secs_f64(game_time),
+            ),
+            TimeSchema::Seconds(seconds) => core::Time::from(Duration::from_secs_f64(seconds)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TimerSnapshotSchema {
+    #[serde(deserialize_with = "deserialize_unix_secs")]
+    started_at: f64,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_optional_unix_secs"
+    )]
+    paused_at: Option<f64>,
+    #[serde(
+        default,
+        skip_serializing_if = "is_zero",
+        deserialize_with = "deserialize_seconds"
+    )]
+    paused_time: f64,
+    #[serde(
+        default,
+        skip_serializing_if = "is_zero",
+        deserialize_with = "deserialize_signed_seconds"
+    )]
+    offset: f64,
+    #[serde(deserialize_with = "deserialize_signed_seconds")]
+    game_time: f64,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_optional_unix_secs"
+    )]
+    game_time_since: Option<f64>,
+    #[serde(default)]
+    game_time_paused: bool,
+    splits: Vec<Option<TimeSchema>>,
+    #[serde(default)]
+    finished: bool,
+}
+
+impl From<&core::TimerSnapshot> for TimerSnapshotSchema {
+    fn from(snapshot: &core::TimerSnapshot) -> Self {
+        Self {
+            started_at: to_unix_secs(snapshot.started_at),
+            paused_at: snapshot.paused_at.map(to_unix_secs),
+            paused_time: snapshot.paused_time.as_secs_f64(),
+            offset: snapshot.offset.as_secs_f64(),
+            game_time: snapshot.game_time.as_secs_f64(),
+            game_time_since: snapshot.game_time_since.map(to_unix_secs),
+            game_time_paused: snapshot.game_time_paused,
+            splits: snapshot
+                .splits
+                .iter()
+                .map(|split| match split {
+                    core::Split::Skipped => None,
+                    core::Split::Split(time) => Some(TimeSchema::from(*time)),
+                })
+                .collect(),
+            finished: snapshot.finished,
+        }
+    }
+}
+
+impl From<TimerSnapshotSchema> for core::TimerSnapshot {
+    fn from(snapshot: TimerSnapshotSchema) -> Self {
+        Self {
+            started_at: from_unix_secs(snapshot.started_at),
+            paused_at: snapshot.paused_at.map(from_unix_secs),
+            paused_time: Duration::from_secs_f64(snapshot.paused_time),
+            offset: core::SignedDuration::from_secs_f64(snapshot.offset),
+            game_time: core::SignedDuration::from_secs_f64(snapshot.game_time),
+            game_time_since: snapshot.game_time_since.map(from_unix_secs),
+            game_time_paused: snapshot.game_time_paused,
+            splits: snapshot
+                .splits
+                .into_iter()
+                .map(|split| match split {
+                    None => core::Split::Skipped,
+                    Some(time) => core::Split::Split(time.into()),
+                })
+                .collect(),
+            finished: snapshot.finished,
+        }
+    }
+}
+
+fn to_unix_secs(time: SystemTime) -> f64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+fn from_unix_secs(secs: f64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs_f64(secs)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimerCommandSchema {
+    StartOrSplit,
+    Start,
+    Split,
+    Skip,
+    Undo,
+    Redo,
+    Pause,
+    Resume,
+    TogglePause,
+    PauseGameTime,
+    ResumeGameTime,
+    ToggleGameTimePause,
+    Reset { save: ResetDecisionSchema },
+    SwitchComparison,
+    ToggleTimingMethod,
+}
+
+impl From<core::TimerCommand> for TimerCommandSchema {
+    fn from(command: core::TimerCommand) -> Self {
+        use core::TimerCommand as Command;
+        match command {
+            Command::StartOrSplit => Self::StartOrSplit,
+            Command::Start => Self::Start,
+            Command::Split => Self::Split,
+            Command::Skip => Self::Skip,
+            Command::Undo => Self::Undo,
+            Command::Redo => Self::Redo,
+            Command::Pause => Self::Pause,
+            Command::Resume => Self::Resume,
+            Command::TogglePause => Self::TogglePause,
+            Command::PauseGameTime => Self::PauseGameTime,
+            Command::ResumeGameTime => Self::ResumeGameTime,
+            Command::ToggleGameTimePause => Self::ToggleGameTimePause,
+            Command::Reset { save } => Self::Reset { save: save.into() },
+            Command::SwitchComparison => Self::SwitchComparison,
+            Command::ToggleTimingMethod => Self::ToggleTimingMethod,
+        }
+    }
+}
+
+impl From<TimerCommandSchema> for core::TimerCommand {
+    fn from(command: TimerCommandSchema) -> Self {
+        use TimerCommandSchema as Schema;
+        match command {
+            Schema::StartOrSplit => Self::StartOrSplit,
+            Schema::Start => Self::Start,
+            Schema::Split => Self::Split,
+            Schema::Skip => Self::Skip,
+            Schema::Undo => Self::Undo,
+            Schema::Redo => Self::Redo,
+            Schema::Pause => Self::Pause,
+            Schema::Resume => Self::Resume,
+            Schema::TogglePause => Self::TogglePause,
+            Schema::PauseGameTime => Self::PauseGameTime,
+            Schema::ResumeGameTime => Self::ResumeGameTime,
+            Schema::ToggleGameTimePause => Self::ToggleGameTimePause,
+            Schema::Reset { save } => Self::Reset { save: save.into() },
+            Schema::SwitchComparison => Self::SwitchComparison,
+            Schema::ToggleTimingMethod => Self::ToggleTimingMethod,
+        }
+    }
+}
+
+/// Either `true` to keep everything or `false` to throw the attempt away, or which
+/// parts of it to keep.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ResetDecisionSchema {
+    Save(bool),
+    Decision {
+        record_attempt: bool,
+        keep_golds: bool,
+        accept_personal_best: bool,
+        #[serde(default = "default_true")]
+        personal_best_on_finish: bool,
+    },
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl From<core::ResetDecision> for ResetDecisionSchema {
+    fn from(decision: core::ResetDecision) -> Self {
+        if Some(decision) == core::ResetPolicy::Always.decision() {
+            Self::Save(true)
+        } else if Some(decision) == core::ResetPolicy::Discard.decision() {
+            Self::Save(false)
+        } else {
+            Self::Decision {
+                record_attempt: decision.record_attempt,
+                keep_golds: decision.keep_golds,
+                accept_personal_best: decision.accept_personal_best,
+                personal_best_on_finish: decision.personal_best_on_finish,
+            }
+        }
+    }
+}
+
+impl From<ResetDecisionSchema> for core::ResetDecision {
+    fn from(decision: ResetDecisionSchema) -> Self {
+        match decision {
+            ResetDecisionSchema::Save(true) => core::ResetPolicy::Always.decision().unwrap(),
+            ResetDecisionSchema::Save(false) => core::ResetPolicy::Discard.decision().unwrap(),
+            ResetDecisionSchema::Decision {
+                record_attempt,
+                keep_golds,
+                accept_personal_best,
+                personal_best_on_finish,
+            } => Self {
+                record_attempt,
+                keep_golds,
+                accept_personal_best,
+                personal_best_on_finish,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_run(yaml: &str) -> core::Run {
+        serde_yaml::from_str::<RunSchema>(yaml).unwrap().into()
+    }
+
+    fn save_run(run: &core::Run) -> String {
+        serde_yaml::to_string(&RunSchema::from(run)).unwrap()
+    }
+
+    #[test]
+    fn legacy_history() {
+        let yaml = "
+title: legacy
+segments:
+  - title: a
+    best_time: 1.0
+    best_segment: 1.0
+    history: [1.0, 1.5, 2.0]
+  - title: b
+    best_time: 3.0
+    best_segment: 1.5
+    history: [3.0, 3.5, 4.0]
+  - title: c
+    best_time: 6.0
+    best_segment: 2.5
+    history: [6.0, 7.0, 6.5]
+";
+        let mut run = load_run(yaml);
+        assert!(run.attempts().is_empty());
+        assert!(run.survival().iter().all(|segment| segment.reached == 0));
+        assert!(run.recompute_bests().is_empty());
+
+        let reloaded = load_run(&save_run(&run));
+        assert_eq!(
+            reloaded.segments()[2].legacy_history(),
+            run.segments()[2].legacy_history()
+        );
+        assert_eq!(reloaded.segments()[2].legacy_history().len(), 3);
+    }
+
+    #[test]
+    fn duplicate_category() {
+        let yaml = "
+title: game
+categories:
+  - title: Any%
+    segments: [{title: a}]
+    variables: {platform: PC}
+  - title: Any%
+    segments: [{title: a}]
+    variables: {platform: Console}
+";
+        let game = serde_yaml::from_str::<GameSchema>(yaml).unwrap();
+        assert!(matches!(
+            core::Game::try_from(game),
+            Err(core::GameError::DuplicateCategory(name)) if name == "Any%"
+        ));
+    }
+
+    fn load_error(yaml: &str) -> String {
+        serde_yaml::from_str::<RunSchema>(yaml)
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test]
+    fn negative_times() {
+        for yaml in [
+            "{title: r, segments: [{title: a, best_time: -1.0}]}",
+            "{title: r, segments: [{group: g, segments: [{title: a, best_time: '-0:01'}]}]}",
+            "{title: r, segments: [{title: a}], attempts: [{id: 1, splits: [-1.0]}]}",
+            "{title: r, segments: [{title: a}], attempts: [{id: 1, splits: [{real_time: 1.0, game_time: -1.0}]}]}",
+            "{title: r, segments: [{title: a}], personal_best: {real_time: [-1.0]}}",
+            "{title: r, segments: [{title: a}], attempts: [{id: 1, started: -5.0, splits: [1.0]}]}",
+        ] {
+            let error = load_error(yaml);
+            assert!(error.contains("negative"), "{yaml}: {error}");
+        }
+        let error = load_error(
+            "{title: r, segments: [{title: a}], attempts: [{id: 1, started: 9.3e18, splits: [1.0]}]}",
+        );
+        assert!(error.contains("too far"), "{error}");
+
+        let snapshot = "{started_at: 0, paused_time: -1.0, game_time: 0, splits: []}";
+        let error = serde_yaml::from_str::<TimerSnapshotSchema>(snapshot).unwrap_err();
+        assert!(error.to_string().contains("negative"), "{error}");
+    }
+
+    #[test]
+    fn malformed_segments() {
+        assert!(load_error("{title: r, segments: [{group: g}]}").contains("segments"));
+        assert!(load_error("{title: r, segments: [{best_time: 1.0}]}").contains("title"));
+        assert!(load_error("{title: r, segments: [{title: a, best_time: soon}]}").contains("soon"));
+    }
+
+    fn secs(secs: f64) -> Option<core::Time> {
+        Some(core::Time::from(Duration::from_secs_f64(secs)))
+    }
+
+    fn run() -> core::Run {
+        let segments = ["a", "b", "c"].map(core::Segment::new).to_vec();
+        let mut run = core::Run::new("test".to_string(), segments);
+        let started = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let ended = started + Duration::from_secs(10);
+        let splits = vec![secs(1.5), None, secs(6.25)];
+        let mut finished =
+            core::Attempt::new(1, Some(started), Some(ended), Duration::ZERO, splits);
+        finished.exclude(0);
+        run.add_attempt(finished);
+        let splits = vec![secs(1.25), secs(3.0), None];
+        let mut reset = core::Attempt::new(2, Some(ended), None, Duration::from_secs(2), splits);
+        reset.set_reset_segment(Some(2));
+        run.add_attempt(reset);
+        run.add_group(core::SegmentGroup::new("group", 1..3))
+            .unwrap();
+        run.set_start_offset(core::SignedDuration::from_secs_f64(-1.5));
+        run
+    }
+
+    #[test]
+    fn run_round_trip() {
+        let run = run();
+        let yaml = save_run(&run);
+        let reloaded = load_run(&yaml);
+        assert_eq!(reloaded.attempts(), run.attempts());
+        assert_eq!(reloaded.groups(), run.groups());
+        assert_eq!(reloaded.start_offset(), run.start_offset());
+        for method in [core::TimingMethod::RealTime, core::TimingMethod::GameTime] {
+            assert_eq!(reloaded.personal_best(method), run.personal_best(method));
+        }
+        for (reloaded, segment) in reloaded.segments().iter().zip(run.segments()) {
+            assert_eq!(reloaded.title(), segment.title());
+            assert_eq!(reloaded.best_time(), segment.best_time());
+            assert_eq!(reloaded.best_segment(), segment.best_segment());
+        }
+        assert_eq!(save_run(&reloaded), yaml);
+    }
+
+    #[test]
+    fn game_round_trip() {
+        let mut game = core::Game::new("game".to_string());
+        let mut category = core::Category::new(run());
+        category.set_variable("platform".to_string(), "PC".to_string());
+        game.add_category(category).unwrap();
+        let segments = vec![core::Segment::new("a")];
+        let category = core::Category::new(core::Run::new("other".to_string(), segments));
+        game.add_category(category).unwrap();
+
+        let yaml = serde_yaml::to_string(&GameSchema::from(&game)).unwrap();
+        let schema = serde_yaml::from_str::<GameSchema>(&yaml).unwrap();
+        let reloaded = core::Game::try_from(schema).unwrap();
+        assert_eq!(reloaded.title(), "game");
+        let names: Vec<_> = reloaded
+            .categories()
+            .iter()
+            .map(core::Category::name)
+            .collect();
+        assert_eq!(names, ["test", "other"]);
+        let category = reloaded.category("test").unwrap();
+        assert_eq!(category.variable("platform"), Some("PC"));
+        assert_eq!(
+            category.run().attempts(),
+            game.categories()[0].run().attempts()
+        );
+        assert!(reloaded.category("other").unwrap().variables().is_empty());
+        assert_eq!(
+            serde_yaml::to_string(&GameSchema::from(&reloaded)).unwrap(),
+            yaml
+        );
+    }
+
+    #[test]
+    fn attempt_segments_out_of_range() {
+        for attempt in [
+            "{id: 3, splits: [1.0, null], reset_segment: 2}",
+            "{id: 3, splits: [1.0, 2.0], excluded: [0, 2]}",
+        ] {
+            let yaml = format!("{{title: r, segments: [{{title: a}}], attempts: [{attempt}]}}");
+            let error = load_error(&yaml);
+            assert!(
+                error.contains("attempt 3 refers to segment 2, but only has 2 splits"),
+                "{attempt}: {error}"
+            );
+        }
+        let yaml = "{title: r, segments: [{title: a}, {title: b}], attempts: [{id: 3, splits: [1.0, null], reset_segment: 1}]}";
+        assert_eq!(load_run(yaml).attempts()[0].reset_segment(), Some(1));
     }
 }